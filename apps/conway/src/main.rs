@@ -6,6 +6,8 @@ extern crate alloc;
 use core::alloc::{GlobalAlloc, Layout};
 use core::panic::PanicInfo;
 use usrlib::{print, println};
+use usrlib::game_loop::{self, GameLoop, LoopControl};
+use usrlib::random::Rng;
 use usrlib::syscall::Syscall;
 
 struct SyscallAllocator;
@@ -36,23 +38,6 @@ impl Grid {
     }
 }
 
-struct Rng {
-    state: u64,
-}
-
-impl Rng {
-    fn new(seed: u64) -> Self {
-        Self { state: seed }
-    }
-
-    fn next_bool(&mut self, threshold: u64) -> bool {
-        self.state = self.state
-            .wrapping_mul(6364136223846793005)
-            .wrapping_add(1442695040888963407);
-        (self.state >> 33) % 100 < threshold
-    }
-}
-
 fn randomize(grid: &mut Grid, rng: &mut Rng) {
     for row in 0..ROWS {
         for col in 0..COLS {
@@ -122,49 +107,70 @@ fn render(grid: &Grid, generation: usize, population: usize, delay_ms: u64, paus
     }
 }
 
-#[unsafe(no_mangle)]
-pub extern "C" fn _start() {
-    print!("\x1B[2J\x1B[H");
-
-    let mut rng = Rng::new(0xDEAD_BEEF_CAFE_BABE);
-    let mut current = Grid::new();
-    let mut next = Grid::new();
-    let mut generation = 0usize;
-    let mut population = 0usize;
-    let mut paused = false;
-    let mut delay_ms: u64 = 100;
+struct Conway {
+    rng: Rng,
+    current: Grid,
+    next: Grid,
+    generation: usize,
+    population: usize,
+    paused: bool,
+    delay_ms: u64,
+}
 
-    randomize(&mut current, &mut rng);
+impl GameLoop for Conway {
+    fn update(&mut self, _dt_ms: u64) {
+        self.population = step(&self.current, &mut self.next);
+        core::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+    }
 
-    loop {
-        render(&current, generation, population, delay_ms, paused);
-        if delay_ms > 0 {
-            Syscall::sleep(delay_ms);
-        }
+    fn render(&self) {
+        render(&self.current, self.generation, self.population, self.delay_ms, self.paused);
+        Syscall::flush();
+    }
 
-        while let Some(c) = Syscall::try_read_char() {
-            match c {
-                ' ' => paused = !paused,
-                '+' | '=' => delay_ms = delay_ms.saturating_sub(50),
-                '-' => delay_ms = (delay_ms + 50).min(1000),
-                'r' | 'R' => {
-                    randomize(&mut current, &mut rng);
-                    generation = 0;
-                    population = 0;
-                }
-                'q' | 'Q' => return,
-                _ => {}
+    fn handle_key(&mut self, c: char) -> LoopControl {
+        match c {
+            ' ' => {
+                self.paused = !self.paused;
+                return LoopControl::Pause;
             }
+            '+' | '=' => self.delay_ms = self.delay_ms.saturating_sub(50),
+            '-' => self.delay_ms = (self.delay_ms + 50).min(1000),
+            'r' | 'R' => {
+                randomize(&mut self.current, &mut self.rng);
+                self.generation = 0;
+                self.population = 0;
+            }
+            'q' | 'Q' => return LoopControl::Quit,
+            _ => {}
         }
+        LoopControl::Continue
+    }
 
-        if !paused {
-            population = step(&current, &mut next);
-            core::mem::swap(&mut current, &mut next);
-            generation += 1;
-        }
+    fn frame_ms(&self) -> u64 {
+        self.delay_ms
     }
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() {
+    print!("\x1B[2J\x1B[H");
+
+    let mut conway = Conway {
+        rng: Rng::from_seed(0xDEAD_BEEF_CAFE_BABE),
+        current: Grid::new(),
+        next: Grid::new(),
+        generation: 0,
+        population: 0,
+        paused: false,
+        delay_ms: 100,
+    };
+    randomize(&mut conway.current, &mut conway.rng);
+
+    game_loop::run(&mut conway);
+}
+
 #[panic_handler]
 fn panic(_info: &PanicInfo) -> ! {
     loop {}
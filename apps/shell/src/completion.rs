@@ -0,0 +1,65 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Completion {
+    Unique(String),
+    Ambiguous(Vec<String>),
+    None,
+}
+
+pub fn complete(prefix: &str, builtins: &[&str]) -> Completion {
+    let candidates: Vec<&str> = builtins.iter().copied().filter(|name| name.starts_with(prefix)).collect();
+    match candidates.as_slice() {
+        [] => Completion::None,
+        [only] => Completion::Unique(only.to_string()),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(*first, |common, name| common_prefix(common, name));
+            if common.len() > prefix.len() {
+                Completion::Unique(common.to_string())
+            } else {
+                Completion::Ambiguous(candidates.iter().map(|name| name.to_string()).collect())
+            }
+        }
+    }
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((index, _), _)| index);
+    &a[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const BUILTINS: [&str; 6] = ["clear", "conway", "ls", "sleep", "tests", "tetris"];
+
+    #[test]
+    fn unique_match_completes_full_name() {
+        assert_eq!(complete("cl", &BUILTINS), Completion::Unique("clear".to_string()));
+    }
+
+    #[test]
+    fn ambiguous_matches_extend_to_common_prefix() {
+        assert_eq!(complete("t", &BUILTINS), Completion::Unique("te".to_string()));
+    }
+
+    #[test]
+    fn ambiguous_matches_without_common_extension_list_candidates() {
+        assert_eq!(
+            complete("c", &BUILTINS),
+            Completion::Ambiguous(vec!["clear".to_string(), "conway".to_string()])
+        );
+    }
+
+    #[test]
+    fn no_match_returns_none() {
+        assert_eq!(complete("x", &BUILTINS), Completion::None);
+    }
+}
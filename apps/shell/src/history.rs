@@ -0,0 +1,134 @@
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+
+pub struct History {
+    entries: VecDeque<String>,
+    capacity: usize,
+    cursor: Option<usize>,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            cursor: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, line: &str) {
+        self.cursor = None;
+        let line = line.trim();
+        if line.is_empty() || self.capacity == 0 || self.entries.back().is_some_and(|last| last == line) {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(line.to_string());
+    }
+
+    pub fn previous(&mut self) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let last = self.entries.len() - 1;
+        let index = match self.cursor {
+            None | Some(0) => last,
+            Some(index) => index - 1,
+        };
+        self.cursor = Some(index);
+        self.entries.get(index).map(String::as_str)
+    }
+
+    pub fn next(&mut self) -> Option<&str> {
+        let index = match self.cursor {
+            None => return None,
+            Some(index) if index + 1 >= self.entries.len() => 0,
+            Some(index) => index + 1,
+        };
+        self.cursor = Some(index);
+        self.entries.get(index).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_keeps_only_the_most_recent_entries() {
+        let mut history = History::new(2);
+        history.push("ls");
+        history.push("clear");
+        history.push("pi");
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.previous(), Some("pi"));
+        assert_eq!(history.previous(), Some("clear"));
+    }
+
+    #[test]
+    fn push_skips_consecutive_duplicates_and_blank_lines() {
+        let mut history = History::new(4);
+        history.push("ls");
+        history.push("ls");
+        history.push("   ");
+        history.push("clear");
+        history.push("ls");
+
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn previous_wraps_from_oldest_to_newest() {
+        let mut history = History::new(4);
+        history.push("ls");
+        history.push("clear");
+
+        assert_eq!(history.previous(), Some("clear"));
+        assert_eq!(history.previous(), Some("ls"));
+        assert_eq!(history.previous(), Some("clear"));
+    }
+
+    #[test]
+    fn next_wraps_from_newest_to_oldest() {
+        let mut history = History::new(4);
+        history.push("ls");
+        history.push("clear");
+
+        assert_eq!(history.next(), None);
+        assert_eq!(history.previous(), Some("clear"));
+        assert_eq!(history.next(), Some("ls"));
+        assert_eq!(history.next(), Some("clear"));
+    }
+
+    #[test]
+    fn push_resets_navigation() {
+        let mut history = History::new(4);
+        history.push("ls");
+        history.push("clear");
+        history.previous();
+        history.previous();
+
+        history.push("pi");
+
+        assert_eq!(history.previous(), Some("pi"));
+    }
+
+    #[test]
+    fn empty_history_has_nothing_to_navigate() {
+        let mut history = History::new(4);
+        assert!(history.is_empty());
+        assert_eq!(history.previous(), None);
+        assert_eq!(history.next(), None);
+    }
+}
@@ -9,4 +9,7 @@ extern crate system;
 extern crate usrlib;
 
 pub mod command;
+pub mod completion;
+pub mod history;
+pub mod line_editor;
 pub mod shell;
@@ -0,0 +1,173 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+pub struct LineEditor {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl Default for LineEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        LineEditor {
+            chars: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn line(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn clear(&mut self) {
+        self.chars.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert(&mut self, c: char) -> String {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+        let mut output = String::from(c);
+        output.push_str(&self.redraw_tail(0));
+        output
+    }
+
+    pub fn backspace(&mut self) -> String {
+        if self.cursor == 0 {
+            return String::new();
+        }
+        self.cursor -= 1;
+        self.chars.remove(self.cursor);
+        let mut output = String::from('\x08');
+        output.push_str(&self.redraw_tail(1));
+        output
+    }
+
+    pub fn left(&mut self) -> String {
+        if self.cursor == 0 {
+            return String::new();
+        }
+        self.cursor -= 1;
+        String::from("\x1B[D")
+    }
+
+    pub fn right(&mut self) -> String {
+        if self.cursor == self.chars.len() {
+            return String::new();
+        }
+        self.cursor += 1;
+        String::from("\x1B[C")
+    }
+
+    fn redraw_tail(&self, erased: usize) -> String {
+        let tail = &self.chars[self.cursor..];
+        if tail.is_empty() && erased == 0 {
+            return String::new();
+        }
+        let mut output: String = tail.iter().collect();
+        output.extend(core::iter::repeat_n(' ', erased));
+        output.push_str(&format!("\x1B[{}D", tail.len() + erased));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_str(editor: &mut LineEditor, s: &str) {
+        s.chars().for_each(|c| {
+            editor.insert(c);
+        });
+    }
+
+    #[test]
+    fn insert_at_end_echoes_only_the_character() {
+        let mut editor = LineEditor::new();
+        assert_eq!(editor.insert('l'), "l");
+        assert_eq!(editor.insert('s'), "s");
+        assert_eq!(editor.line(), "ls");
+    }
+
+    #[test]
+    fn insert_in_the_middle_redraws_tail_and_restores_cursor() {
+        let mut editor = LineEditor::new();
+        type_str(&mut editor, "lp");
+        editor.left();
+
+        let output = editor.insert('s');
+
+        assert_eq!(editor.line(), "lsp");
+        assert_eq!(editor.cursor(), 2);
+        assert_eq!(output, "sp\x1B[1D");
+    }
+
+    #[test]
+    fn backspace_at_end_erases_last_character() {
+        let mut editor = LineEditor::new();
+        type_str(&mut editor, "ls");
+
+        let output = editor.backspace();
+
+        assert_eq!(editor.line(), "l");
+        assert_eq!(output, "\x08 \x1B[1D");
+    }
+
+    #[test]
+    fn backspace_in_the_middle_shifts_tail_left() {
+        let mut editor = LineEditor::new();
+        type_str(&mut editor, "lxs");
+        editor.left();
+
+        let output = editor.backspace();
+
+        assert_eq!(editor.line(), "ls");
+        assert_eq!(editor.cursor(), 1);
+        assert_eq!(output, "\x08s \x1B[2D");
+    }
+
+    #[test]
+    fn backspace_at_start_does_nothing() {
+        let mut editor = LineEditor::new();
+        type_str(&mut editor, "ls");
+        editor.left();
+        editor.left();
+
+        assert_eq!(editor.backspace(), "");
+        assert_eq!(editor.line(), "ls");
+    }
+
+    #[test]
+    fn cursor_movement_stops_at_line_boundaries() {
+        let mut editor = LineEditor::new();
+        type_str(&mut editor, "a");
+
+        assert_eq!(editor.right(), "");
+        assert_eq!(editor.left(), "\x1B[D");
+        assert_eq!(editor.left(), "");
+        assert_eq!(editor.cursor(), 0);
+        assert_eq!(editor.right(), "\x1B[C");
+        assert_eq!(editor.cursor(), 1);
+    }
+
+    #[test]
+    fn clear_resets_line_and_cursor() {
+        let mut editor = LineEditor::new();
+        type_str(&mut editor, "ls");
+
+        editor.clear();
+
+        assert_eq!(editor.line(), "");
+        assert_eq!(editor.cursor(), 0);
+    }
+}
@@ -1,9 +1,13 @@
 use usrlib::{print, println};
 use usrlib::syscall::Syscall;
 use alloc::string::String;
+use alloc::vec::Vec;
 use alloc::collections::BTreeMap;
 use lazy_static::lazy_static;
 use crate::command::Command;
+use crate::completion::{complete, Completion};
+use crate::history::History;
+use crate::line_editor::LineEditor;
 
 #[cfg(target_arch = "x86_64")]
 static PI_ELF: &[u8] = include_bytes!("../../../apps/hello_elf/target/rosx-user/release/hello_elf");
@@ -26,11 +30,14 @@ static CONWAY_ELF: &[u8] = include_bytes!("../../../apps/conway/target/rosx-user
 static CONWAY_ELF: &[u8] = include_bytes!("../../../apps/conway/target/rosx-i686-user/release/conway");
 
 static PROMPT: &str = "\x1B[32mrose>\x1B[m ";
+const HISTORY_SIZE: usize = 32;
+const DMESG_BYTES: usize = 4096;
 
 lazy_static! {
     static ref COMMANDS: BTreeMap<String, fn()> = BTreeMap::from([
         (String::from("ls"), ls as fn()),
         (String::from("clear"), clear as fn()),
+        (String::from("dmesg"), dmesg as fn()),
         (String::from("rose"), rose as fn()),
         (String::from("pi"), pi as fn()),
         (String::from("snake"), snake as fn()),
@@ -48,15 +55,18 @@ pub fn main() {
     rose();
     prompt();
     
-    let mut buffer = String::new();
+    let mut editor = LineEditor::new();
+    let mut history = History::new(HISTORY_SIZE);
     
     loop {
         let c = Syscall::read_char();
         
         if c == '\n' {
             println!();
+            let line = editor.line();
+            history.push(&line);
 
-            if let Some(cmd) = Command::parse(&buffer) {
+            if let Some(cmd) = Command::parse(&line) {
                 if let Some(command) = COMMANDS.get(&cmd.name) {
                     command();
                 } else {
@@ -64,16 +74,14 @@ pub fn main() {
                 }
             }
             
-            buffer.clear();
+            editor.clear();
             prompt();
+        } else if c == '\t' {
+            complete_command(&mut editor);
         } else if c == '\x08' {
-            if !buffer.is_empty() {
-                buffer.pop();
-                print!("\x08");
-            }
+            print!("{}", editor.backspace());
         } else {
-            print!("{}", c);
-            buffer.push(c);
+            print!("{}", editor.insert(c));
         }
     }
 }
@@ -82,6 +90,27 @@ fn prompt() {
     print!("{}", PROMPT);
 }
 
+fn complete_command(editor: &mut LineEditor) {
+    let line = editor.line();
+    if line.contains(char::is_whitespace) || editor.cursor() != line.chars().count() {
+        return;
+    }
+    let builtins: Vec<&str> = COMMANDS.keys().map(String::as_str).collect();
+    match complete(&line, &builtins) {
+        Completion::Unique(name) => {
+            name[line.len()..].chars().for_each(|c| print!("{}", editor.insert(c)));
+        }
+        Completion::Ambiguous(candidates) => {
+            println!();
+            candidates.iter().for_each(|candidate| print!("{}\t", candidate));
+            println!();
+            prompt();
+            print!("{}", line);
+        }
+        Completion::None => {}
+    }
+}
+
 fn rose() {
     println!("\x1B[40m\x1B[31m       _");
     println!("\x1B[40m\x1B[31m     _( )_");
@@ -97,6 +126,12 @@ fn clear() {
     print!("\x1B[2J\x1B[H");
 }
 
+fn dmesg() {
+    let mut buf = alloc::vec![0u8; DMESG_BYTES];
+    let count = Syscall::read_kernel_log(&mut buf);
+    print!("{}", String::from_utf8_lossy(&buf[..count]));
+}
+
 fn ls() {
     COMMANDS.iter().for_each(|(command, _)| print!("{}\t", command));
     println!();
@@ -7,6 +7,7 @@ use alloc::collections::VecDeque;
 use core::alloc::{GlobalAlloc, Layout};
 use core::panic::PanicInfo;
 use usrlib::{print, println};
+use usrlib::random::Rng;
 use usrlib::syscall::Syscall;
 
 struct SyscallAllocator;
@@ -53,23 +54,6 @@ impl Dir {
     }
 }
 
-struct Rng {
-    state: u64,
-}
-
-impl Rng {
-    fn new(seed: u64) -> Self {
-        Self { state: seed }
-    }
-
-    fn next_usize(&mut self, max: usize) -> usize {
-        self.state = self.state
-            .wrapping_mul(6364136223846793005)
-            .wrapping_add(1442695040888963407);
-        (self.state >> 33) as usize % max
-    }
-}
-
 fn random_food(snake: &VecDeque<Pos>, rng: &mut Rng) -> Pos {
     loop {
         let pos = Pos {
@@ -209,7 +193,7 @@ fn play(rng: &mut Rng) -> bool {
 
 #[unsafe(no_mangle)]
 pub extern "C" fn _start() {
-    let mut rng = Rng::new(0xDEAD_BEEF_CAFE_BABE);
+    let mut rng = Rng::from_clock();
 
     print!("\x1B[2J\x1B[H");
 
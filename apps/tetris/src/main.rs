@@ -6,6 +6,7 @@ extern crate alloc;
 use core::alloc::{GlobalAlloc, Layout};
 use core::panic::PanicInfo;
 use usrlib::{print, println};
+use usrlib::random::Rng;
 use usrlib::syscall::Syscall;
 
 struct SyscallAllocator;
@@ -52,23 +53,6 @@ const TETROMINOES: [TetrominoType; 7] = [
     TetrominoType { rotations: [0x2E00, 0x4460, 0x0E80, 0xC440], color: 7 }, // L - white
 ];
 
-struct Rng {
-    state: u64,
-}
-
-impl Rng {
-    fn new(seed: u64) -> Self {
-        Self { state: seed }
-    }
-
-    fn next_usize(&mut self, max: usize) -> usize {
-        self.state = self.state
-            .wrapping_mul(6364136223846793005)
-            .wrapping_add(1442695040888963407);
-        (self.state >> 33) as usize % max
-    }
-}
-
 struct Piece {
     kind: usize,
     rotation: usize,
@@ -292,7 +276,7 @@ fn play(rng: &mut Rng) -> bool {
 
 #[unsafe(no_mangle)]
 pub extern "C" fn _start() {
-    let mut rng = Rng::new(0xDEAD_BEEF_CAFE_BABE);
+    let mut rng = Rng::from_clock();
 
     loop {
         print!("\x1B[2J\x1B[H");
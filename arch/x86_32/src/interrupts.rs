@@ -159,6 +159,7 @@ pub struct InterruptStackFrame {
 extern "x86-interrupt" fn timer_interrupt_handler(_frame: InterruptStackFrame) {
     SYSTEM_TIME_MS.fetch_add(MS_PER_TICK as u32, Relaxed);
     unsafe { outb(PIC_MASTER_CMD, PIC_EOI) };
+    crate::vga_buffer::end_bell();
     kernel().preempt();
 }
 
@@ -170,14 +171,14 @@ extern "x86-interrupt" fn keyboard_interrupt_handler(_frame: InterruptStackFrame
 
 // ── Port I/O ──────────────────────────────────────────────────────────────────
 
-unsafe fn outb(port: u16, value: u8) {
+pub(crate) unsafe fn outb(port: u16, value: u8) {
     unsafe {
         asm!("out dx, al", in("dx") port, in("al") value,
              options(nomem, nostack, preserves_flags));
     }
 }
 
-unsafe fn inb(port: u16) -> u8 {
+pub(crate) unsafe fn inb(port: u16) -> u8 {
     let value: u8;
     unsafe {
         asm!("in al, dx", out("al") value, in("dx") port,
@@ -4,7 +4,6 @@
 
 extern crate alloc;
 
-mod ansi_parser;
 mod cpu;
 mod debug_console;
 mod elf_arch;
@@ -45,6 +44,9 @@ extern "C" fn kernel_main(multiboot_magic: u32, multiboot_info: u32) -> ! {
         panic!("bad Multiboot magic: {:#x}", multiboot_magic);
     }
 
+    vga_buffer::set_default_colors(vga_buffer::Color::Green, vga_buffer::Color::Black);
+    vga_buffer::enable_cursor();
+
     let raw_blocks = parse_memory_map(multiboot_info as *const u8);
     let memory_blocks = trim_to_safe_memory(raw_blocks);
     kernel::kernel::bootstrap(&memory_blocks, &MULTIPLEXED_OUTPUT);
@@ -109,6 +109,7 @@ extern "x86-interrupt" fn timer_interrupt_handler(_stack_frame: InterruptStackFr
             .notify_end_of_interrupt(InterruptIndex::Timer.as_u8());
     }
 
+    crate::framebuffer::tick();
     kernel().preempt();
 }
 
@@ -8,14 +8,12 @@ mod debug_console;
 mod elf_arch;
 mod interrupts;
 mod vga_buffer;
-mod terminal_fonts;
 mod framebuffer;
-mod ansi_parser;
 
 use crate::cpu::X86_64;
 use crate::debug_console::QemuDebugConsole;
 use crate::elf_arch::X86_64ElfArch;
-use crate::framebuffer::FramebufferOutput;
+use crate::framebuffer::{FramebufferOutput, WrapMode};
 use bootloader_api::{entry_point, BootInfo, BootloaderConfig};
 use bootloader_api::config::Mapping;
 use core::panic::PanicInfo;
@@ -27,6 +25,7 @@ use kernel::scheduler;
 use kernel::kprintln;
 use kernel::panic::handle_panic;
 use kernel::task::{FunctionTask};
+use terminal::fonts::TERMINUS_8X16;
 
 static FB_OUTPUT: FramebufferOutput = FramebufferOutput;
 pub static QEMU_OUTPUT: QemuDebugConsole = QemuDebugConsole;
@@ -59,11 +58,17 @@ fn panic(info: &PanicInfo) -> ! {
 fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
     if let Some(fb) = boot_info.framebuffer.as_mut() {
         framebuffer::init(fb.buffer_mut().as_mut_ptr() as u64, fb.info());
+        framebuffer::set_default_colors((0, 255, 0), (0, 0, 0));
+        framebuffer::set_font(&TERMINUS_8X16);
+        framebuffer::set_wrap_mode(WrapMode::CharWrap);
+        framebuffer::enable_cursor(true);
     }
     let phys_offset = boot_info.physical_memory_offset.into_option().unwrap();
     unsafe { cpu::clear_nx_bits(phys_offset); }
     let memory_blocks = build_memory_blocks(boot_info, phys_offset);
     kernel::kernel::bootstrap(&memory_blocks, &MULTIPLEXED_OUTPUT);
+    framebuffer::enable_double_buffering();
+    framebuffer::enable_read_back();
     kprintln!("[KERNEL] Initializing");
     let mut kernel = Kernel::new(&KCONFIG);
     kernel.setup();
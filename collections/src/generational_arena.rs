@@ -60,6 +60,36 @@ impl<T, const S: usize> GenerationalArena<T, S> {
         Self { items, generations, free_slots }
     }
 
+    pub fn len(&self) -> usize {
+        self.items.len() - self.free_slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn reserve(&mut self, additional: usize) -> Result<(), Error> {
+        let max_slots = HalfSize::MAX as usize + 1;
+        let start = self.items.len();
+        let end = match start.checked_add(additional) {
+            Some(end) if end <= max_slots => end,
+            _ => return Err(Error::OutOfMemory),
+        };
+        self.items.reserve_exact(additional);
+        self.generations.reserve_exact(additional);
+        self.free_slots.reserve_exact(additional);
+        for slot in start..end {
+            self.items.push(None);
+            self.generations.push(0);
+            self.free_slots.push_back(slot as HalfSize);
+        }
+        Ok(())
+    }
+
     pub fn add(&mut self, item: T) -> Result<Handle, Error> {
         match self.free_slots.pop_front() {
             Some(index) => {
@@ -87,6 +117,29 @@ impl<T, const S: usize> GenerationalArena<T, S> {
         Err(Error::NotFound)
     }
 
+    pub fn contains(&self, handle: Handle) -> bool {
+        let index = handle.index as usize;
+        index < self.items.len()
+            && self.generations[index] == handle.generation
+            && self.items[index].is_some()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        let index = handle.index as usize;
+        if index < self.items.len() && self.generations[index] == handle.generation {
+            return self.items[index].as_ref();
+        }
+        None
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        let index = handle.index as usize;
+        if index < self.items.len() && self.generations[index] == handle.generation {
+            return self.items[index].as_mut();
+        }
+        None
+    }
+
     pub fn remove(&mut self, handle: Handle) -> Result<T, Error> {
         let index = handle.index as usize;
         if index >= self.items.len() || self.generations[index] != handle.generation {
@@ -98,6 +151,38 @@ impl<T, const S: usize> GenerationalArena<T, S> {
         Ok(item)
     }
 
+    pub fn retain<F: FnMut(Handle, &T) -> bool>(&mut self, mut f: F) {
+        for index in 0..self.items.len() {
+            let handle = Handle::new(index as HalfSize, self.generations[index]);
+            let keep = match &self.items[index] {
+                Some(item) => f(handle, item),
+                None => true,
+            };
+            if !keep {
+                self.items[index] = None;
+                self.generations[index] = self.generations[index].wrapping_add(1);
+                self.free_slots.push_back(handle.index);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for item in self.items.iter_mut() {
+            item.take();
+        }
+        for generation in self.generations.iter_mut() {
+            *generation = generation.wrapping_add(1);
+        }
+        self.free_slots.clear();
+        self.free_slots.extend((0..self.items.len()).map(|slot| slot as HalfSize));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.items.iter().enumerate().filter_map(|(index, item)| {
+            item.as_ref().map(|item| (Handle::new(index as HalfSize, self.generations[index]), item))
+        })
+    }
+
     pub fn replace(&mut self, handle: Handle, item: T) -> Result<Handle, Error> {
         let index = handle.index as usize;
         if index >= self.items.len() || self.generations[index] != handle.generation {
@@ -297,4 +382,203 @@ mod tests {
         assert_eq!(h5.index, 1);
         assert_eq!(h6.index, 2);
     }
+
+    #[test]
+    fn should_get_when_handle_is_valid() {
+        let mut arena: GenerationalArena<i32, 5> = GenerationalArena::new();
+        let handle = arena.add(42).unwrap();
+        assert_eq!(arena.get(handle), Some(&42));
+    }
+
+    #[test]
+    fn should_get_mut_when_handle_is_valid() {
+        let mut arena: GenerationalArena<i32, 5> = GenerationalArena::new();
+        let handle = arena.add(10).unwrap();
+
+        *arena.get_mut(handle).unwrap() = 20;
+
+        assert_eq!(arena.get(handle), Some(&20));
+    }
+
+    #[test]
+    fn should_get_none_when_handle_was_removed() {
+        let mut arena: GenerationalArena<i32, 5> = GenerationalArena::new();
+        let handle = arena.add(42).unwrap();
+
+        arena.remove(handle).unwrap();
+
+        assert_eq!(arena.get(handle), None);
+        assert_eq!(arena.get_mut(handle), None);
+    }
+
+    #[test]
+    fn should_get_none_when_index_is_out_of_range() {
+        let mut arena: GenerationalArena<i32, 5> = GenerationalArena::new();
+        let handle = Handle::new(5, 0);
+
+        assert_eq!(arena.get(handle), None);
+        assert_eq!(arena.get_mut(handle), None);
+    }
+
+    #[test]
+    fn should_get_none_when_slot_was_never_filled() {
+        let arena: GenerationalArena<i32, 5> = GenerationalArena::new();
+        assert_eq!(arena.get(Handle::new(2, 0)), None);
+    }
+
+    #[test]
+    fn should_be_empty_when_created() {
+        let arena: GenerationalArena<i32, 5> = GenerationalArena::new();
+        assert_eq!(arena.len(), 0);
+        assert!(arena.is_empty());
+    }
+
+    #[test]
+    fn should_track_len_when_adding_and_removing() {
+        let mut arena: GenerationalArena<i32, 5> = GenerationalArena::new();
+        let h1 = arena.add(1).unwrap();
+        let h2 = arena.add(2).unwrap();
+        assert_eq!(arena.len(), 2);
+        assert!(!arena.is_empty());
+
+        arena.remove(h1).unwrap();
+        assert_eq!(arena.len(), 1);
+
+        arena.remove(h2).unwrap();
+        assert_eq!(arena.len(), 0);
+        assert!(arena.is_empty());
+    }
+
+    #[test]
+    fn should_report_capacity_as_len_when_full() {
+        let mut arena: GenerationalArena<i32, 3> = GenerationalArena::new();
+        arena.add(1).unwrap();
+        arena.add(2).unwrap();
+        arena.add(3).unwrap();
+        assert_eq!(arena.add(4), Err(Error::OutOfMemory));
+        assert_eq!(arena.len(), 3);
+    }
+
+    #[test]
+    fn should_not_change_len_when_removing_stale_handle() {
+        let mut arena: GenerationalArena<i32, 3> = GenerationalArena::new();
+        let handle = arena.add(1).unwrap();
+        arena.remove(handle).unwrap();
+        assert!(arena.remove(handle).is_err());
+        assert_eq!(arena.len(), 0);
+    }
+
+    #[test]
+    fn should_retain_only_matching_items() {
+        let mut arena: GenerationalArena<i32, 6> = GenerationalArena::new();
+        let handles: Vec<Handle> = (0..6).map(|value| arena.add(value).unwrap()).collect();
+
+        arena.retain(|_, value| value % 2 == 0);
+
+        assert_eq!(arena.len(), 3);
+        for (value, handle) in handles.iter().enumerate() {
+            if value % 2 == 0 {
+                assert_eq!(arena.get(*handle), Some(&(value as i32)));
+            } else {
+                assert_eq!(arena.get(*handle), None);
+                assert_eq!(arena.remove(*handle), Err(Error::NotFound));
+            }
+        }
+    }
+
+    #[test]
+    fn should_reuse_slots_freed_by_retain() {
+        let mut arena: GenerationalArena<i32, 2> = GenerationalArena::new();
+        let h1 = arena.add(1).unwrap();
+        arena.add(2).unwrap();
+
+        arena.retain(|handle, _| handle != h1);
+        let h3 = arena.add(3).unwrap();
+
+        assert_eq!(h3.index, h1.index);
+        assert_ne!(h3.generation, h1.generation);
+        assert_eq!(arena.get(h3), Some(&3));
+    }
+
+    #[test]
+    fn reserve_adds_free_slots_without_reallocating_on_add() {
+        let mut arena: GenerationalArena<i32, 2> = GenerationalArena::new();
+        arena.add(1).unwrap();
+        arena.add(2).unwrap();
+
+        arena.reserve(3).unwrap();
+        let items_ptr = arena.items.as_ptr();
+        let generations_ptr = arena.generations.as_ptr();
+
+        let handles: Vec<Handle> = (3..6).map(|value| arena.add(value).unwrap()).collect();
+
+        assert_eq!(arena.capacity(), 5);
+        assert_eq!(arena.len(), 5);
+        assert_eq!(arena.items.as_ptr(), items_ptr);
+        assert_eq!(arena.generations.as_ptr(), generations_ptr);
+        assert_eq!(arena.get(handles[2]), Some(&5));
+        assert_eq!(arena.add(6), Err(Error::OutOfMemory));
+    }
+
+    #[test]
+    fn reserve_rejects_growth_past_the_index_range() {
+        let mut arena: GenerationalArena<i32, 2> = GenerationalArena::new();
+
+        assert_eq!(arena.reserve(HalfSize::MAX as usize), Err(Error::OutOfMemory));
+        assert_eq!(arena.reserve(usize::MAX), Err(Error::OutOfMemory));
+        assert_eq!(arena.capacity(), 2);
+    }
+
+    #[test]
+    fn contains_is_true_for_live_handle() {
+        let mut arena: GenerationalArena<i32, 2> = GenerationalArena::new();
+        let handle = arena.add(1).unwrap();
+
+        assert!(arena.contains(handle));
+    }
+
+    #[test]
+    fn contains_is_false_for_removed_handle() {
+        let mut arena: GenerationalArena<i32, 2> = GenerationalArena::new();
+        let handle = arena.add(1).unwrap();
+        arena.remove(handle).unwrap();
+
+        assert!(!arena.contains(handle));
+    }
+
+    #[test]
+    fn contains_is_false_for_out_of_range_index() {
+        let arena: GenerationalArena<i32, 2> = GenerationalArena::new();
+
+        assert!(!arena.contains(Handle::new(2, 0)));
+    }
+
+    #[test]
+    fn clear_invalidates_handles_and_restarts_from_first_slot() {
+        let mut arena: GenerationalArena<String, 3> = GenerationalArena::new();
+        let first = arena.add("first".to_string()).unwrap();
+        let second = arena.add("second".to_string()).unwrap();
+
+        arena.clear();
+
+        assert!(arena.is_empty());
+        assert_eq!(arena.borrow(first), Err(Error::NotFound));
+        assert_eq!(arena.remove(second), Err(Error::NotFound));
+        let handle = arena.add("third".to_string()).unwrap();
+        assert_eq!(handle.index, 0);
+        assert_eq!(arena.borrow(handle).unwrap(), "third");
+    }
+
+    #[test]
+    fn iter_yields_live_items_with_their_handles() {
+        let mut arena: GenerationalArena<u32, 4> = GenerationalArena::new();
+        let first = arena.add(1).unwrap();
+        let removed = arena.add(2).unwrap();
+        let third = arena.add(3).unwrap();
+        arena.remove(removed).unwrap();
+
+        let items: Vec<(Handle, u32)> = arena.iter().map(|(handle, item)| (handle, *item)).collect();
+
+        assert_eq!(items, vec![(first, 1), (third, 3)]);
+    }
 }
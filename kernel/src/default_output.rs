@@ -1,8 +1,10 @@
 use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
 
 use crate::once::Once;
 
 static DEFAULT_OUTPUT: Once<&'static dyn KernelOutput> = Once::new();
+const DEFAULT_TERMINAL_SIZE: (u16, u16) = (80, 25);
 
 struct KernelWriter;
 
@@ -19,43 +21,506 @@ impl Write for KernelWriter {
 
 pub trait KernelOutput: Send + Sync {
     fn write_str(&self, s: &str);
+
+    fn terminal_size(&self) -> Option<(u16, u16)> {
+        None
+    }
+
+    fn put_char_at(&self, _col: u16, _row: u16, _ch: u8, _fg: u8, _bg: u8) {}
+
+    fn flush(&self) {}
+
+    fn hexdump(&self, bytes: &[u8], base_addr: usize) {
+        let mut writer = OutputWriter(self);
+        for (line, chunk) in bytes.chunks(HEXDUMP_WIDTH).enumerate() {
+            let _ = write!(writer, "{:08x} ", base_addr + line * HEXDUMP_WIDTH);
+            for i in 0..HEXDUMP_WIDTH {
+                if i % 8 == 0 {
+                    self.write_str(" ");
+                }
+                match chunk.get(i) {
+                    Some(byte) => { let _ = write!(writer, "{:02x} ", byte); }
+                    None => self.write_str("   "),
+                }
+            }
+            self.write_str(" |");
+            for &byte in chunk {
+                let c = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+                let _ = writer.write_char(c);
+            }
+            self.write_str("|\n");
+        }
+    }
+}
+
+const HEXDUMP_WIDTH: usize = 16;
+
+struct OutputWriter<'a, O: KernelOutput + ?Sized>(&'a O);
+
+impl<O: KernelOutput + ?Sized> Write for OutputWriter<'_, O> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s);
+        Ok(())
+    }
+}
+
+struct LoggedOutput;
+
+impl KernelOutput for LoggedOutput {
+    fn write_str(&self, s: &str) {
+        crate::kernel_log::append(s);
+        if let Some(output) = DEFAULT_OUTPUT.get() {
+            output.write_str(s);
+        }
+    }
 }
 
 pub(crate) fn setup_default_output(output: &'static dyn KernelOutput) {
     DEFAULT_OUTPUT.call_once(|| output);
 }
 
+pub fn terminal_size() -> (u16, u16) {
+    DEFAULT_OUTPUT
+        .get()
+        .and_then(|output| output.terminal_size())
+        .unwrap_or(DEFAULT_TERMINAL_SIZE)
+}
+
+pub fn put_char_at(col: u16, row: u16, ch: u8, fg: u8, bg: u8) {
+    if let Some(output) = DEFAULT_OUTPUT.get() {
+        output.put_char_at(col, row, ch, fg, bg);
+    }
+}
+
+pub fn flush() {
+    if let Some(output) = DEFAULT_OUTPUT.get() {
+        output.flush();
+    }
+}
+
 pub struct MultiplexOutput {
     outputs: &'static [&'static dyn KernelOutput],
+    enabled: AtomicU32,
 }
 
 impl MultiplexOutput {
     pub const fn new(outputs: &'static [&'static dyn KernelOutput]) -> Self {
-        Self { outputs }
+        assert!(outputs.len() <= u32::BITS as usize, "too many outputs");
+        Self { outputs, enabled: AtomicU32::new(u32::MAX) }
+    }
+
+    pub fn set_enabled(&self, index: usize, enabled: bool) {
+        if index >= self.outputs.len() {
+            return;
+        }
+        if enabled {
+            self.enabled.fetch_or(1 << index, Ordering::Relaxed);
+        } else {
+            self.enabled.fetch_and(!(1 << index), Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_enabled(&self, index: usize) -> bool {
+        index < self.outputs.len() && self.enabled.load(Ordering::Relaxed) & (1 << index) != 0
     }
 }
 
 impl KernelOutput for MultiplexOutput {
     fn write_str(&self, s: &str) {
-        for output in self.outputs {
-            output.write_str(s);
+        let enabled = self.enabled.load(Ordering::Relaxed);
+        for (index, output) in self.outputs.iter().enumerate() {
+            if enabled & (1 << index) != 0 {
+                output.write_str(s);
+            }
+        }
+    }
+
+    fn terminal_size(&self) -> Option<(u16, u16)> {
+        self.outputs
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| self.is_enabled(index))
+            .find_map(|(_, output)| output.terminal_size())
+    }
+
+    fn put_char_at(&self, col: u16, row: u16, ch: u8, fg: u8, bg: u8) {
+        let enabled = self.enabled.load(Ordering::Relaxed);
+        for (index, output) in self.outputs.iter().enumerate() {
+            if enabled & (1 << index) != 0 {
+                output.put_char_at(col, row, ch, fg, bg);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        let enabled = self.enabled.load(Ordering::Relaxed);
+        for (index, output) in self.outputs.iter().enumerate() {
+            if enabled & (1 << index) != 0 {
+                output.flush();
+            }
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+    Trace = 4,
+}
+
+pub struct LogFilter {
+    level: AtomicU8,
+}
+
+impl LogFilter {
+    pub const fn new(level: LogLevel) -> Self {
+        LogFilter { level: AtomicU8::new(level as u8) }
+    }
+
+    pub fn set_level(&self, level: LogLevel) {
+        self.level.store(level as u8, Ordering::Relaxed);
+    }
+
+    pub fn allows(&self, level: LogLevel) -> bool {
+        level as u8 <= self.level.load(Ordering::Relaxed)
+    }
+
+    pub fn write(&self, output: &dyn KernelOutput, level: LogLevel, args: fmt::Arguments) {
+        if self.allows(level) {
+            let _ = OutputWriter(output).write_fmt(args);
         }
     }
 }
 
+static LOG_FILTER: LogFilter = LogFilter::new(LogLevel::Info);
+
+pub fn set_log_level(level: LogLevel) {
+    LOG_FILTER.set_level(level);
+}
+
 #[doc(hidden)]
 pub fn print(args: fmt::Arguments) {
     use core::fmt::Write;
     KernelWriter.write_fmt(args).unwrap();
 }
 
+pub fn write(fd: usize, bytes: &[u8]) -> usize {
+    write_fd(&mut KernelWriter, fd, bytes)
+}
+
+fn write_fd(writer: &mut impl Write, fd: usize, bytes: &[u8]) -> usize {
+    if fd != system::fd::STDOUT && fd != system::fd::STDERR {
+        return 0;
+    }
+    for chunk in bytes.utf8_chunks() {
+        let _ = writer.write_str(chunk.valid());
+        if !chunk.invalid().is_empty() {
+            let _ = writer.write_char(char::REPLACEMENT_CHARACTER);
+        }
+    }
+    bytes.len()
+}
+
+#[doc(hidden)]
+pub fn log(level: LogLevel, args: fmt::Arguments) {
+    LOG_FILTER.write(&LoggedOutput, level, args);
+}
+
+#[macro_export]
+macro_rules! klog {
+      ($level:expr, $($arg:tt)*) => ($crate::default_output::log($level, format_args!("{}\n", format_args!($($arg)*))));
+  }
+
 #[macro_export]
 macro_rules! kprintln {
-      () => ($crate::kprint!("\n"));
-      ($($arg:tt)*) => ($crate::kprint!("{}\n", format_args!($($arg)*)));
+      () => ($crate::default_output::log($crate::default_output::LogLevel::Info, format_args!("\n")));
+      ($($arg:tt)*) => ($crate::klog!($crate::default_output::LogLevel::Info, $($arg)*));
   }
 
 #[macro_export]
 macro_rules! kprint {
       ($($arg:tt)*) => ($crate::default_output::print(format_args!($($arg)*)));
   }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::boxed::Box;
+    use std::string::String;
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    struct RecordingOutput {
+        written: Mutex<String>,
+        size: Option<(u16, u16)>,
+        cells: Mutex<Vec<(u16, u16, u8, u8, u8)>>,
+        flushes: AtomicU32,
+    }
+
+    impl RecordingOutput {
+        fn new() -> Self {
+            RecordingOutput { written: Mutex::new(String::new()), size: None, cells: Mutex::new(Vec::new()), flushes: AtomicU32::new(0) }
+        }
+
+        fn sized(cols: u16, rows: u16) -> Self {
+            RecordingOutput { written: Mutex::new(String::new()), size: Some((cols, rows)), cells: Mutex::new(Vec::new()), flushes: AtomicU32::new(0) }
+        }
+
+        fn written(&self) -> String {
+            self.written.lock().unwrap().clone()
+        }
+    }
+
+    impl KernelOutput for RecordingOutput {
+        fn write_str(&self, s: &str) {
+            self.written.lock().unwrap().push_str(s);
+        }
+
+        fn terminal_size(&self) -> Option<(u16, u16)> {
+            self.size
+        }
+
+        fn put_char_at(&self, col: u16, row: u16, ch: u8, fg: u8, bg: u8) {
+            self.cells.lock().unwrap().push((col, row, ch, fg, bg));
+        }
+
+        fn flush(&self) {
+            self.flushes.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn leak(output: RecordingOutput) -> &'static RecordingOutput {
+        Box::leak(Box::new(output))
+    }
+
+    fn multiplex(sinks: &[&'static RecordingOutput]) -> MultiplexOutput {
+        let outputs: Vec<&'static dyn KernelOutput> = sinks.iter().map(|&sink| sink as &dyn KernelOutput).collect();
+        MultiplexOutput::new(Vec::leak(outputs))
+    }
+
+    #[test]
+    fn multiplex_reports_size_of_first_sink_that_knows_it() {
+        let console = leak(RecordingOutput::new());
+        let vga = leak(RecordingOutput::sized(80, 25));
+        let framebuffer = leak(RecordingOutput::sized(160, 50));
+        let output = multiplex(&[console, vga, framebuffer]);
+
+        assert_eq!(output.terminal_size(), Some((80, 25)));
+    }
+
+    #[test]
+    fn multiplex_size_skips_disabled_sinks() {
+        let vga = leak(RecordingOutput::sized(80, 25));
+        let framebuffer = leak(RecordingOutput::sized(160, 50));
+        let output = multiplex(&[vga, framebuffer]);
+
+        output.set_enabled(0, false);
+
+        assert_eq!(output.terminal_size(), Some((160, 50)));
+    }
+
+    #[test]
+    fn multiplex_size_is_unknown_without_sized_sinks() {
+        let console = leak(RecordingOutput::new());
+        let output = multiplex(&[console]);
+
+        assert_eq!(output.terminal_size(), None);
+    }
+
+    #[test]
+    fn multiplex_writes_to_all_sinks_by_default() {
+        let first = leak(RecordingOutput::new());
+        let second = leak(RecordingOutput::new());
+        let output = multiplex(&[first, second]);
+
+        output.write_str("boot");
+
+        assert_eq!(first.written(), "boot");
+        assert_eq!(second.written(), "boot");
+    }
+
+    #[test]
+    fn disabled_sink_receives_nothing() {
+        let first = leak(RecordingOutput::new());
+        let second = leak(RecordingOutput::new());
+        let output = multiplex(&[first, second]);
+
+        output.set_enabled(0, false);
+        output.write_str("quiet");
+
+        assert!(!output.is_enabled(0));
+        assert_eq!(first.written(), "");
+        assert_eq!(second.written(), "quiet");
+    }
+
+    #[test]
+    fn re_enabled_sink_receives_later_writes() {
+        let first = leak(RecordingOutput::new());
+        let output = multiplex(&[first]);
+
+        output.set_enabled(0, false);
+        output.write_str("muted ");
+        output.set_enabled(0, true);
+        output.write_str("back");
+
+        assert_eq!(first.written(), "back");
+    }
+
+    #[test]
+    fn out_of_range_index_is_ignored() {
+        let first = leak(RecordingOutput::new());
+        let output = multiplex(&[first]);
+
+        output.set_enabled(5, true);
+
+        assert!(!output.is_enabled(5));
+        assert!(output.is_enabled(0));
+    }
+
+    #[test]
+    fn write_fd_writes_whole_slice_to_stdout_and_stderr() {
+        let output = RecordingOutput::new();
+
+        assert_eq!(write_fd(&mut OutputWriter(&output), system::fd::STDOUT, b"hello "), 6);
+        assert_eq!(write_fd(&mut OutputWriter(&output), system::fd::STDERR, b"world"), 5);
+
+        assert_eq!(output.written(), "hello world");
+    }
+
+    #[test]
+    fn write_fd_replaces_invalid_utf8_but_counts_every_byte() {
+        let output = RecordingOutput::new();
+
+        assert_eq!(write_fd(&mut OutputWriter(&output), system::fd::STDOUT, b"a\xFFb"), 3);
+
+        assert_eq!(output.written(), "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn write_fd_rejects_unknown_descriptors() {
+        let output = RecordingOutput::new();
+
+        assert_eq!(write_fd(&mut OutputWriter(&output), 0, b"ignored"), 0);
+        assert_eq!(write_fd(&mut OutputWriter(&output), 3, b"ignored"), 0);
+
+        assert_eq!(output.written(), "");
+    }
+
+    #[test]
+    fn log_filter_drops_messages_below_threshold() {
+        let output = RecordingOutput::new();
+        let filter = LogFilter::new(LogLevel::Warn);
+
+        filter.write(&output, LogLevel::Error, format_args!("error "));
+        filter.write(&output, LogLevel::Warn, format_args!("warn "));
+        filter.write(&output, LogLevel::Info, format_args!("info "));
+        filter.write(&output, LogLevel::Debug, format_args!("debug "));
+
+        assert_eq!(output.written(), "error warn ");
+    }
+
+    #[test]
+    fn log_filter_level_can_be_changed_at_runtime() {
+        let filter = LogFilter::new(LogLevel::Info);
+        assert!(!filter.allows(LogLevel::Trace));
+
+        filter.set_level(LogLevel::Trace);
+        assert!(filter.allows(LogLevel::Trace));
+
+        filter.set_level(LogLevel::Error);
+        assert!(!filter.allows(LogLevel::Warn));
+        assert!(filter.allows(LogLevel::Error));
+    }
+
+    #[test]
+    fn hexdump_formats_partial_line_with_ascii_column() {
+        let output = RecordingOutput::new();
+
+        output.hexdump(b"Hello World\n", 0);
+
+        assert_eq!(
+            output.written(),
+            "00000000  48 65 6c 6c 6f 20 57 6f  72 6c 64 0a              |Hello World.|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_splits_lines_every_16_bytes_from_base_address() {
+        let output = RecordingOutput::new();
+        let bytes: [u8; 18] = core::array::from_fn(|i| i as u8 + b'a');
+
+        output.hexdump(&bytes, 0x1000);
+
+        assert_eq!(
+            output.written(),
+            "00001000  61 62 63 64 65 66 67 68  69 6a 6b 6c 6d 6e 6f 70  |abcdefghijklmnop|\n\
+             00001010  71 72                                             |qr|\n"
+        );
+    }
+
+    #[test]
+    fn hexdump_of_empty_slice_writes_nothing() {
+        let output = RecordingOutput::new();
+
+        output.hexdump(&[], 0);
+
+        assert_eq!(output.written(), "");
+    }
+
+    fn kernel_log_contains(needle: &[u8]) -> bool {
+        let mut buf = [0u8; crate::kernel_log::KERNEL_LOG_CAPACITY];
+        let len = crate::kernel_log::read(&mut buf);
+        buf[..len].windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[test]
+    fn log_messages_are_kept_in_kernel_log() {
+        log(LogLevel::Error, format_args!("log-path-marker\n"));
+
+        assert!(kernel_log_contains(b"log-path-marker"));
+    }
+
+    #[test]
+    fn log_drops_messages_below_global_level() {
+        log(LogLevel::Trace, format_args!("trace-path-marker\n"));
+
+        assert!(!kernel_log_contains(b"trace-path-marker"));
+    }
+
+    #[test]
+    fn fd_writes_are_not_kept_in_kernel_log() {
+        write(system::fd::STDOUT, b"fd-path-marker");
+
+        assert!(!kernel_log_contains(b"fd-path-marker"));
+    }
+
+    #[test]
+    fn multiplex_puts_chars_on_enabled_sinks_only() {
+        let vga = leak(RecordingOutput::sized(80, 25));
+        let framebuffer = leak(RecordingOutput::sized(160, 50));
+        let output = multiplex(&[vga, framebuffer]);
+        output.set_enabled(1, false);
+
+        output.put_char_at(3, 4, b'#', 9, 0);
+
+        assert_eq!(*vga.cells.lock().unwrap(), vec![(3, 4, b'#', 9, 0)]);
+        assert!(framebuffer.cells.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn multiplex_flushes_enabled_sinks_only() {
+        let console = leak(RecordingOutput::new());
+        let framebuffer = leak(RecordingOutput::new());
+        let output = multiplex(&[console, framebuffer]);
+        output.set_enabled(0, false);
+
+        output.flush();
+
+        assert_eq!(console.flushes.load(Ordering::Relaxed), 0);
+        assert_eq!(framebuffer.flushes.load(Ordering::Relaxed), 1);
+    }
+}
@@ -1,5 +1,7 @@
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::any::Any;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use system::future::FutureHandle;
 use system::future::Future;
 use collections::generational_arena::{Error, GenerationalArena};
@@ -28,19 +30,105 @@ impl Future for TimeFuture {
     }
 }
 
+pub struct TimeoutFuture {
+    deadline: u64,
+}
+
+impl TimeoutFuture {
+    pub fn new(deadline: u64) -> Self {
+        Self { deadline }
+    }
+
+    fn is_expired_at(&self, now: u64) -> bool {
+        now > self.deadline
+    }
+}
+
+impl Future for TimeoutFuture {
+    fn is_completed(&self) -> bool {
+        self.is_expired_at(kernel().get_system_time())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 pub struct TaskCompletionFuture {
     task_handle: TaskHandle,
+    exit_code: Option<i32>,
 }
 
 impl TaskCompletionFuture {
     pub fn new(task_handle: TaskHandle) -> Self {
-        Self { task_handle }
+        Self { task_handle, exit_code: None }
+    }
+
+    pub fn exited(task_handle: TaskHandle, exit_code: i32) -> Self {
+        Self { task_handle, exit_code: Some(exit_code) }
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
     }
 }
 
 impl Future for TaskCompletionFuture {
     fn is_completed(&self) -> bool {
-        services().task_manager.borrow().get_state(self.task_handle) == crate::task::TaskState::Terminated
+        self.exit_code.is_some()
+            || services().task_manager.borrow().get_state(self.task_handle) == crate::task::TaskState::Terminated
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct JoinAll {
+    futures: Vec<Box<dyn Future>>,
+}
+
+impl JoinAll {
+    pub fn new(futures: Vec<Box<dyn Future>>) -> Self {
+        Self { futures }
+    }
+}
+
+impl Future for JoinAll {
+    fn is_completed(&self) -> bool {
+        self.futures.iter().all(|future| future.is_completed())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+const NOT_COMPLETED: usize = usize::MAX;
+
+pub struct SelectAny {
+    futures: Vec<Box<dyn Future>>,
+    completed: AtomicUsize,
+}
+
+impl SelectAny {
+    pub fn new(futures: Vec<Box<dyn Future>>) -> Self {
+        Self { futures, completed: AtomicUsize::new(NOT_COMPLETED) }
+    }
+
+    pub fn completed_index(&self) -> Option<usize> {
+        if self.completed.load(Ordering::Relaxed) == NOT_COMPLETED {
+            if let Some(fired) = self.futures.iter().position(|future| future.is_completed()) {
+                self.completed.store(fired, Ordering::Relaxed);
+            }
+        }
+        Some(self.completed.load(Ordering::Relaxed)).filter(|&index| index != NOT_COMPLETED)
+    }
+}
+
+impl Future for SelectAny {
+    fn is_completed(&self) -> bool {
+        self.completed_index().is_some()
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -98,4 +186,87 @@ impl FutureRegistry {
 
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec;
+    use core::sync::atomic::AtomicBool;
+
+    struct FlagFuture {
+        done: Arc<AtomicBool>,
+    }
+
+    impl Future for FlagFuture {
+        fn is_completed(&self) -> bool {
+            self.done.load(Ordering::Relaxed)
+        }
 
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn flag_future() -> (Arc<AtomicBool>, Box<dyn Future>) {
+        let done = Arc::new(AtomicBool::new(false));
+        (done.clone(), Box::new(FlagFuture { done }))
+    }
+
+    #[test]
+    fn join_all_completes_only_when_every_future_completes() {
+        let (first, first_future) = flag_future();
+        let (second, second_future) = flag_future();
+        let join = JoinAll::new(vec![first_future, second_future]);
+
+        assert!(!join.is_completed());
+        first.store(true, Ordering::Relaxed);
+        assert!(!join.is_completed());
+        second.store(true, Ordering::Relaxed);
+        assert!(join.is_completed());
+    }
+
+    #[test]
+    fn join_all_of_nothing_is_completed() {
+        assert!(JoinAll::new(Vec::new()).is_completed());
+    }
+
+    #[test]
+    fn select_any_completes_when_one_future_completes() {
+        let (_first, first_future) = flag_future();
+        let (second, second_future) = flag_future();
+        let select = SelectAny::new(vec![first_future, second_future]);
+
+        assert!(!select.is_completed());
+        assert_eq!(select.completed_index(), None);
+        second.store(true, Ordering::Relaxed);
+        assert!(select.is_completed());
+        assert_eq!(select.completed_index(), Some(1));
+    }
+
+    #[test]
+    fn select_any_keeps_reporting_the_first_future_that_fired() {
+        let (first, first_future) = flag_future();
+        let (second, second_future) = flag_future();
+        let select = SelectAny::new(vec![first_future, second_future]);
+
+        second.store(true, Ordering::Relaxed);
+        assert!(select.is_completed());
+        first.store(true, Ordering::Relaxed);
+
+        assert_eq!(select.completed_index(), Some(1));
+    }
+
+    #[test]
+    fn select_any_of_nothing_never_completes() {
+        assert!(!SelectAny::new(Vec::new()).is_completed());
+    }
+
+    #[test]
+    fn timeout_future_expires_only_after_deadline_passes() {
+        let timeout = TimeoutFuture::new(100);
+
+        assert!(!timeout.is_expired_at(0));
+        assert!(!timeout.is_expired_at(100));
+        assert!(timeout.is_expired_at(101));
+    }
+}
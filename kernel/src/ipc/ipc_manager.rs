@@ -7,14 +7,13 @@ use system::future::{ Future, FutureHandle };
 use system::ipc::IpcError::ServerNotFound;
 use system::ipc::{IpcError, IpcReply, IpcReplyFuture, IpcSendMessage, IpcServerHandle};
 use crate::ipc::ipc_server::IpcServerConnection;
-use crate::kernel::kernel;
 use crate::kernel_services::services;
 use crate::task::TaskHandle;
 
 pub(crate) struct IpcReceiveMessage {
     pub value: u32,
     pub sender: TaskHandle,
-    pub future: FutureHandle
+    pub future: Option<FutureHandle>
 }
 
 pub(crate) struct IpcReplyMessage {
@@ -62,21 +61,29 @@ impl IpcManager {
         Ok(handle)
     }
 
-    pub(crate) fn send(&mut self, handle: IpcServerHandle, message: IpcSendMessage) -> FutureHandle {
-        let sender = kernel().execution_state.current_task.unwrap();
+    pub(crate) fn send(&mut self, handle: IpcServerHandle, sender: TaskHandle, message: IpcSendMessage) -> FutureHandle {
         let mailbox = self.mailboxes.get_mut(handle.index as usize).unwrap();
         let future = Box::new(IpcReplyFuture { reply: None });
         let future_handle = services().future_registry.borrow_mut().register(future).unwrap();
         let receive_message = IpcReceiveMessage {
             value: message.value,
             sender,
-            future: future_handle,
+            future: Some(future_handle),
         };
         mailbox.push_back(receive_message);
 
         future_handle
     }
 
+    pub(crate) fn notify(&mut self, handle: IpcServerHandle, sender: TaskHandle, message: IpcSendMessage) {
+        let mailbox = self.mailboxes.get_mut(handle.index as usize).unwrap();
+        mailbox.push_back(IpcReceiveMessage {
+            value: message.value,
+            sender,
+            future: None,
+        });
+    }
+
     pub(crate) fn receive(&mut self, handle: IpcServerHandle) -> Option<IpcReceiveMessage> {
         let mailbox = self.mailboxes.get_mut(handle.index as usize).unwrap();
         if mailbox.is_empty() {
@@ -93,4 +100,53 @@ impl IpcManager {
         let _ = services().future_registry.borrow_mut().replace(future_handle, future);
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel_services::init;
+    use crate::task::Task;
+
+    fn make_sender() -> TaskHandle {
+        init();
+        let task = Task::new("Sender", 0, 0);
+        services().task_manager.borrow_mut().add_task(task).unwrap()
+    }
+
+    #[test]
+    fn notify_delivers_message_without_reply_future() {
+        let sender = make_sender();
+        let mut manager = IpcManager::new();
+        let server = manager.register("SERVICE").unwrap();
+
+        manager.notify(server, sender, IpcSendMessage { value: 7 });
+
+        let message = manager.receive(server).unwrap();
+        assert_eq!(message.value, 7);
+        assert_eq!(message.sender, sender);
+        assert!(message.future.is_none());
+        assert!(manager.receive(server).is_none());
+    }
+
+    #[test]
+    fn send_after_notify_is_received_as_request() {
+        let sender = make_sender();
+        let mut manager = IpcManager::new();
+        let server = manager.register("SERVICE").unwrap();
+
+        manager.notify(server, sender, IpcSendMessage { value: 1 });
+        let future_handle = manager.send(server, sender, IpcSendMessage { value: 2 });
+
+        let notification = manager.receive(server).unwrap();
+        assert!(notification.future.is_none());
+        let request = manager.receive(server).unwrap();
+        assert_eq!(request.value, 2);
+        assert_eq!(request.future, Some(future_handle));
+
+        manager.reply(IpcReplyMessage { value: 3, destination: sender, future: future_handle });
+        let future = services().future_registry.borrow_mut().consume(future_handle).unwrap();
+        let reply = future.as_any().downcast_ref::<IpcReplyFuture>().unwrap();
+        assert_eq!(reply.reply, Some(IpcReply { value: 3 }));
+    }
+}
@@ -22,13 +22,15 @@ impl RandomGeneratorServer {
             .unwrap();
         loop {
             if let Some(message) = services().ipc_manager.borrow_mut().receive(biding) {
-                let value = self.next();
-                let reply = IpcReplyMessage {
-                    value,
-                    destination: message.sender,
-                    future: message.future,
-                };
-                services().ipc_manager.borrow_mut().reply(reply);
+                if let Some(future) = message.future {
+                    let value = self.next();
+                    let reply = IpcReplyMessage {
+                        value,
+                        destination: message.sender,
+                        future,
+                    };
+                    services().ipc_manager.borrow_mut().reply(reply);
+                }
             } else {
                 Self::sleep();
             }
@@ -9,11 +9,12 @@ use crate::messages::HardwareInterrupt;
 use crate::scheduler::Scheduler;
 use crate::state::{ExecutionContext, ExecutionState};
 use crate::task::TaskState::Terminated;
-use crate::task::{SharedTask, Task, TaskHandle, YieldReason};
+use crate::task::{new_named_entrypoint_task, SharedTask, Task, TaskHandle, YieldReason, MAX_TASK_NAME_LEN};
 use alloc::boxed::Box;
 use core::ptr::null_mut;
 use collections::generational_arena::Error;
 use system::future::{Future, FutureHandle };
+use system::task::TaskInfo;
 #[cfg(not(test))]
 use crate::memory::memory_manager::MEMORY_MANAGER;
 #[cfg(not(test))]
@@ -21,6 +22,7 @@ use crate::memory::MemoryBlocks;
 use crate::kernel_cell::KernelCell;
 
 static KERNEL_PTR: KernelCell<*mut Kernel> = KernelCell::new(null_mut());
+const KILLED_EXIT_CODE: i32 = -1;
 
 pub fn kernel() -> &'static mut Kernel {
     unsafe { &mut **KERNEL_PTR.borrow() }
@@ -30,6 +32,7 @@ pub struct Kernel {
     cpu: &'static dyn Cpu,
     pub(crate) elf_arch: &'static dyn ElfArch,
     scheduler: Box<dyn Scheduler>,
+    idle_task: Option<TaskHandle>,
     pub(crate) execution_state: ExecutionState,
 }
 
@@ -57,6 +60,7 @@ impl Kernel {
             cpu,
             elf_arch,
             scheduler,
+            idle_task: None,
             execution_state: ExecutionState {
                 scheduler: scheduler_task_handler,
                 current_task: None,
@@ -85,6 +89,7 @@ impl Kernel {
                 .borrow_task_mut(task_handle)
                 .unwrap(),
         );
+        self.idle_task = Some(task_handle);
         let _ = self.scheduler.set_idle_task(task_handle);
     }
 
@@ -101,10 +106,23 @@ impl Kernel {
     }
 
     pub fn schedule(&mut self, task: SharedTask) -> Result<FutureHandle, ()> {
+        let (_, future_handle) = self.add_and_schedule(task)?;
+        future_handle.ok_or(())
+    }
+
+    pub fn spawn(&mut self, name: &str, entrypoint: usize) -> Option<TaskHandle> {
+        if name.len() > MAX_TASK_NAME_LEN {
+            return None;
+        }
+        let (task_handle, _) = self.add_and_schedule(new_named_entrypoint_task(name, entrypoint)).ok()?;
+        Some(task_handle)
+    }
+
+    fn add_and_schedule(&mut self, task: SharedTask) -> Result<(TaskHandle, Option<FutureHandle>), ()> {
         let prev = self.execution_state.preemption_enabled;
         self.execution_state.preemption_enabled = false;
         let result = services().task_manager.borrow_mut().add_task(task);
-        let future_handle = match result {
+        let handles = match result {
             Ok(task_handle) => {
                 let future = Box::new(TaskCompletionFuture::new(task_handle));
                 let future_handle = services().future_registry.borrow_mut().register(future);
@@ -112,14 +130,17 @@ impl Kernel {
                     services().task_manager.borrow_mut().set_completion_future(task_handle, fh);
                 }
                 self.schedule_task(task_handle);
-                future_handle
-            }
-            Err(_) => {
-                panic!("Not able to create new task");
+                Ok((task_handle, future_handle))
             }
+            Err(_) => Err(()),
         };
         self.execution_state.preemption_enabled = prev;
-        future_handle.ok_or(())
+        handles
+    }
+
+    pub fn schedule_with_priority(&mut self, mut task: SharedTask, priority: u8) -> Result<FutureHandle, ()> {
+        task.set_priority(priority);
+        self.schedule(task)
     }
 
     pub fn enqueue(&mut self, hardware_interrupt: HardwareInterrupt) {
@@ -138,10 +159,74 @@ impl Kernel {
         services().future_registry.borrow_mut().consume(handle)
     }
 
+    pub fn sleep_until(&mut self, deadline: u64) {
+        self.execution_state.block_current_task();
+        let task_handle = self.execution_state.current_task();
+        self.scheduler.push_sleeping(task_handle, deadline);
+        self.execution_state.switch_to_scheduler();
+    }
+
+    pub fn wait_for_task(&mut self, task_handle: TaskHandle) -> Option<i32> {
+        let Some(future_handle) = services().task_manager.borrow().get_completion_future(task_handle) else {
+            return services().task_manager.borrow_mut().collect_exit_code(task_handle);
+        };
+        if services().task_manager.borrow().get_state(task_handle) == Terminated {
+            let _ = services().future_registry.borrow_mut().consume(future_handle);
+            return services().task_manager.borrow_mut().collect_exit_code(task_handle);
+        }
+        let future = self.wait_future(future_handle).ok()?;
+        let _ = services().task_manager.borrow_mut().collect_exit_code(task_handle);
+        future.as_any().downcast_ref::<TaskCompletionFuture>()?.exit_code()
+    }
+
+    pub fn kill(&mut self, task_handle: TaskHandle) -> bool {
+        if task_handle == self.execution_state.scheduler
+            || Some(task_handle) == self.idle_task
+            || Some(task_handle) == self.execution_state.current_task
+        {
+            return false;
+        }
+        if services().task_manager.borrow().get_state(task_handle) == Terminated {
+            return false;
+        }
+        self.record_exit(task_handle, KILLED_EXIT_CODE);
+        services().memory_manager.release_task_memory(task_handle.pack());
+        true
+    }
+
+    fn record_exit(&mut self, task_handle: TaskHandle, exit_code: i32) {
+        let completion_future = {
+            let task_manager = services().task_manager.borrow_mut();
+            task_manager.set_exit_code(task_handle, exit_code);
+            task_manager.set_state(task_handle, Terminated);
+            task_manager.get_completion_future(task_handle)
+        };
+        if let Some(future_handle) = completion_future {
+            let future = Box::new(TaskCompletionFuture::exited(task_handle, exit_code));
+            let _ = services().future_registry.borrow_mut().replace(future_handle, future);
+        }
+    }
+
     pub fn is_future_completed(&self, handle: FutureHandle) -> bool {
         services().future_registry.borrow_mut().get(handle).unwrap_or(true)
     }
 
+    pub fn current_task_id(&self) -> usize {
+        self.execution_state.current_task().pack()
+    }
+
+    pub fn current_task_name(&self, buf: &mut [u8]) -> usize {
+        let task_manager = services().task_manager.borrow();
+        let name = task_manager.get_name(self.execution_state.current_task()).unwrap_or("");
+        let len = name.len().min(buf.len());
+        buf[..len].copy_from_slice(&name.as_bytes()[..len]);
+        len
+    }
+
+    pub fn list_tasks(&self, buf: &mut [TaskInfo]) -> usize {
+        services().task_manager.borrow().fill_task_info(buf)
+    }
+
     pub fn task_yield(&mut self) {
         if let Some(task_handle) = self.execution_state.current_task {
             services().task_manager.borrow_mut().set_yield_reason(task_handle, YieldReason::Voluntary);
@@ -163,12 +248,13 @@ impl Kernel {
     }
 
     pub(crate) fn terminate_and_yield(&mut self) -> ! {
+        self.exit(0)
+    }
+
+    pub(crate) fn exit(&mut self, exit_code: i32) -> ! {
         self.execution_state.preemption_enabled = false;
         if let Some(task_handle) = self.execution_state.current_task.take() {
-            services()
-                .task_manager
-                .borrow_mut()
-                .set_state(task_handle, Terminated);
+            self.record_exit(task_handle, exit_code);
             self.execution_state.current_task = Some(task_handle);
         }
         self.execution_state.switch_to_scheduler();
@@ -213,3 +299,355 @@ extern "C" fn main_thread_run() -> ! {
 
     panic!("Kernel main thread returned");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::ElfArch;
+    use crate::memory::{MemoryBlock, MemoryBlocks};
+    use crate::scheduler::fifo_scheduler;
+    use crate::task::TaskState::{Blocked, Ready};
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    struct TickingCpu {
+        time: AtomicU64,
+    }
+
+    impl Cpu for TickingCpu {
+        fn setup(&self) {}
+        fn enable_interrupts(&self) {}
+        fn disable_interrupts(&self) {}
+        fn are_interrupts_enabled(&self) -> bool { false }
+        fn initialize_stack(&self, _: usize, _: usize, _: usize, _: usize) -> usize { 0 }
+        fn swap_context(&self, _: *mut usize, _: usize) {}
+        fn get_system_time(&self) -> u64 { self.time.fetch_add(1, Ordering::Relaxed) }
+        fn halt(&self) {}
+    }
+
+    struct NoopElfArch;
+
+    impl ElfArch for NoopElfArch {
+        fn apply_relocation(&self, _: usize, _: usize, _: u64, _: i64) {}
+    }
+
+    static CPU: TickingCpu = TickingCpu { time: AtomicU64::new(0) };
+    static ELF_ARCH: NoopElfArch = NoopElfArch;
+    static KCONFIG: KConfig = KConfig {
+        cpu: &CPU,
+        elf_arch: &ELF_ARCH,
+        scheduler_factory: fifo_scheduler,
+    };
+
+    #[test]
+    fn task_yield_marks_current_task_as_voluntary() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let task = Task::new("Yielding", 0, 0);
+        let handle = services().task_manager.borrow_mut().add_task(task).unwrap();
+        kernel.execution_state.current_task = Some(handle);
+
+        kernel.task_yield();
+
+        assert_eq!(
+            services().task_manager.borrow().get_yield_reason(handle),
+            Some(YieldReason::Voluntary)
+        );
+    }
+
+    #[test]
+    fn current_task_id_matches_running_task_handle() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let task = Task::new("Current", 0, 0);
+        let handle = services().task_manager.borrow_mut().add_task(task).unwrap();
+        kernel.execution_state.current_task = Some(handle);
+
+        assert_eq!(TaskHandle::unpack(kernel.current_task_id()), handle);
+    }
+
+    #[test]
+    fn current_task_name_copies_name_into_buffer() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let task = Task::new("Current", 0, 0);
+        let handle = services().task_manager.borrow_mut().add_task(task).unwrap();
+        kernel.execution_state.current_task = Some(handle);
+        let mut buf = [0u8; 16];
+
+        let len = kernel.current_task_name(&mut buf);
+
+        assert_eq!(&buf[..len], b"Current");
+    }
+
+    #[test]
+    fn current_task_name_truncates_to_buffer_length() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let task = Task::new("Current", 0, 0);
+        let handle = services().task_manager.borrow_mut().add_task(task).unwrap();
+        kernel.execution_state.current_task = Some(handle);
+        let mut buf = [0u8; 3];
+
+        let len = kernel.current_task_name(&mut buf);
+
+        assert_eq!(len, 3);
+        assert_eq!(&buf, b"Cur");
+    }
+
+    #[test]
+    fn wait_for_task_blocks_while_target_is_alive() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let waiter = services().task_manager.borrow_mut().add_task(Task::new("Waiter", 0, 0)).unwrap();
+        let child = kernel.spawn("Child", 0).unwrap();
+        let scheduled = kernel.scheduler.task_count();
+        kernel.execution_state.current_task = Some(waiter);
+
+        kernel.wait_for_task(child);
+
+        assert_eq!(services().task_manager.borrow().get_state(waiter), Blocked);
+        assert_eq!(kernel.scheduler.task_count(), scheduled + 1);
+    }
+
+    #[test]
+    fn wait_for_task_returns_exit_code_of_terminated_target() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let waiter = services().task_manager.borrow_mut().add_task(Task::new("Waiter", 0, 0)).unwrap();
+        let child = kernel.spawn("Child", 0).unwrap();
+        kernel.record_exit(child, 42);
+        kernel.execution_state.current_task = Some(waiter);
+
+        assert_eq!(kernel.wait_for_task(child), Some(42));
+        assert_ne!(services().task_manager.borrow().get_state(waiter), Blocked);
+    }
+
+    #[test]
+    fn wait_for_task_returns_exit_code_after_target_was_reaped() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let child = kernel.spawn("Child", 0).unwrap();
+        kernel.record_exit(child, 42);
+        services().task_manager.borrow_mut().remove_task(child);
+
+        assert_eq!(kernel.wait_for_task(child), Some(42));
+        assert_eq!(kernel.wait_for_task(child), None);
+    }
+
+    #[test]
+    fn wait_for_task_returns_none_for_unknown_target() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let child = services().task_manager.borrow_mut().add_task(Task::new("Child", 0, 0)).unwrap();
+        services().task_manager.borrow_mut().remove_task(child);
+
+        assert_eq!(kernel.wait_for_task(child), None);
+    }
+
+    #[test]
+    fn sleep_until_blocks_current_task_in_scheduler() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let sleeper = services().task_manager.borrow_mut().add_task(Task::new("Sleeper", 0, 0)).unwrap();
+        kernel.execution_state.current_task = Some(sleeper);
+        let before = kernel.scheduler.task_count();
+
+        kernel.sleep_until(100);
+
+        assert_eq!(services().task_manager.borrow().get_state(sleeper), Blocked);
+        assert_eq!(kernel.scheduler.task_count(), before + 1);
+    }
+
+    #[test]
+    fn record_exit_stores_exit_code_on_task() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let child = kernel.spawn("Child", 0).unwrap();
+
+        kernel.record_exit(child, 7);
+
+        assert_eq!(services().task_manager.borrow().get_exit_code(child), Some(7));
+        assert_eq!(services().task_manager.borrow().get_state(child), Terminated);
+    }
+
+    #[test]
+    fn task_completion_future_completes_once_target_terminates() {
+        let _kernel = Kernel::new(&KCONFIG);
+        let child = services().task_manager.borrow_mut().add_task(Task::new("Child", 0, 0)).unwrap();
+        let future = TaskCompletionFuture::new(child);
+
+        assert!(!future.is_completed());
+        services().task_manager.borrow_mut().set_state(child, Terminated);
+        assert!(future.is_completed());
+    }
+
+    #[test]
+    fn spawn_schedules_named_task_and_returns_its_handle() {
+        let mut kernel = Kernel::new(&KCONFIG);
+
+        let handle = kernel.spawn("Spawned", 0).unwrap();
+
+        assert_eq!(services().task_manager.borrow().get_name(handle), Some("Spawned"));
+        assert_eq!(services().task_manager.borrow().get_state(handle), Ready);
+        assert!(services().task_manager.borrow().get_completion_future(handle).is_some());
+        assert_eq!(kernel.scheduler.task_count(), 1);
+    }
+
+    #[test]
+    fn spawn_keeps_its_own_copy_of_the_name() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let mut buffer = *b"Borrowed";
+
+        let handle = kernel.spawn(core::str::from_utf8(&buffer).unwrap(), 0).unwrap();
+        buffer.fill(b'x');
+
+        assert_eq!(services().task_manager.borrow().get_name(handle), Some("Borrowed"));
+    }
+
+    #[test]
+    fn spawn_rejects_names_longer_than_limit() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let name = "a-task-name-that-is-far-too-long-to-keep";
+        assert!(name.len() > MAX_TASK_NAME_LEN);
+
+        assert!(kernel.spawn(name, 0).is_none());
+        assert_eq!(kernel.scheduler.task_count(), 0);
+    }
+
+    #[test]
+    fn spawn_fails_without_panicking_when_the_task_table_is_full() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        kernel.execution_state.preemption_enabled = true;
+        let mut fillers = Vec::new();
+        while let Ok(handle) = services().task_manager.borrow_mut().add_task(Task::new("Filler", 0, 0)) {
+            fillers.push(handle);
+        }
+
+        let spawned = kernel.spawn("Spawned", 0);
+        for handle in fillers {
+            services().task_manager.borrow_mut().remove_task(handle);
+        }
+
+        assert!(spawned.is_none());
+        assert!(kernel.execution_state.preemption_enabled);
+    }
+
+    #[test]
+    fn spawned_task_can_be_waited_on_until_it_terminates() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let child = kernel.spawn("Child", 0).unwrap();
+        let completion = services().task_manager.borrow().get_completion_future(child).unwrap();
+
+        assert!(!kernel.is_future_completed(completion));
+        services().task_manager.borrow_mut().set_state(child, Terminated);
+        assert!(kernel.is_future_completed(completion));
+    }
+
+    #[test]
+    fn kill_terminates_a_ready_task() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let target = kernel.spawn("Target", 0).unwrap();
+
+        assert!(kernel.kill(target));
+        assert_eq!(services().task_manager.borrow().get_state(target), Terminated);
+        assert_eq!(kernel.wait_for_task(target), Some(KILLED_EXIT_CODE));
+    }
+
+    #[test]
+    fn kill_releases_memory_the_task_allocated() {
+        let region = Vec::leak(vec![0u8; 64 * 1024]);
+        let blocks = MemoryBlocks {
+            blocks: core::array::from_fn(|i| MemoryBlock {
+                start: if i == 0 { region.as_mut_ptr() as usize } else { 0 },
+                size: if i == 0 { region.len() } else { 0 },
+            }),
+            count: 1,
+        };
+        let mut kernel = Kernel::new(&KCONFIG);
+        services().memory_manager.bootstrap(&blocks);
+        let target = kernel.spawn("Target", 0).unwrap();
+        let before = services().memory_manager.used();
+        let layout = core::alloc::Layout::from_size_align(128, 8).unwrap();
+        let ptr = unsafe { services().memory_manager.alloc_for_task(layout, target.pack()) };
+        assert!(!ptr.is_null());
+        assert_eq!(services().memory_manager.used(), before + 128);
+
+        assert!(kernel.kill(target));
+
+        assert_eq!(services().memory_manager.used(), before);
+    }
+
+    #[test]
+    fn kill_terminates_a_blocked_task() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let target = services().task_manager.borrow_mut().add_task(Task::new("Target", 0, 0)).unwrap();
+        let child = kernel.spawn("Child", 0).unwrap();
+        kernel.execution_state.current_task = Some(target);
+        kernel.wait_for_task(child);
+        kernel.execution_state.current_task = None;
+        assert_eq!(services().task_manager.borrow().get_state(target), Blocked);
+
+        assert!(kernel.kill(target));
+        assert_eq!(services().task_manager.borrow().get_state(target), Terminated);
+    }
+
+    #[test]
+    fn kill_rejects_idle_task_and_main_thread() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let idle = services().task_manager.borrow_mut().add_task(Task::new("Idle", 0, 0)).unwrap();
+        kernel.idle_task = Some(idle);
+        let main_thread = kernel.execution_state.scheduler;
+
+        assert!(!kernel.kill(idle));
+        assert!(!kernel.kill(main_thread));
+        assert_ne!(services().task_manager.borrow().get_state(idle), Terminated);
+        assert_ne!(services().task_manager.borrow().get_state(main_thread), Terminated);
+    }
+
+    #[test]
+    fn kill_rejects_the_calling_task() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let current = kernel.spawn("Current", 0).unwrap();
+        kernel.execution_state.current_task = Some(current);
+
+        assert!(!kernel.kill(current));
+        assert_ne!(services().task_manager.borrow().get_state(current), Terminated);
+        assert_ne!(services().task_manager.borrow().get_exit_code(current), Some(KILLED_EXIT_CODE));
+    }
+
+    #[test]
+    fn kill_rejects_unknown_tasks() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let target = kernel.spawn("Target", 0).unwrap();
+        services().task_manager.borrow_mut().remove_task(target);
+
+        assert!(!kernel.kill(target));
+    }
+
+    #[test]
+    fn list_tasks_reports_ids_names_and_states() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        let ready = kernel.spawn("ListReady", 0).unwrap();
+        let blocked = kernel.spawn("ListBlocked", 0).unwrap();
+        services().task_manager.borrow_mut().set_state(blocked, Blocked);
+        let mut buf = [TaskInfo::empty(); 256];
+
+        let count = kernel.list_tasks(&mut buf);
+        let listed = &buf[..count];
+
+        let ready_info = listed.iter().find(|info| info.id == ready.pack() as u64).unwrap();
+        assert_eq!(ready_info.name(), "ListReady");
+        assert_eq!(ready_info.state_name(), "Ready");
+        let blocked_info = listed.iter().find(|info| info.id == blocked.pack() as u64).unwrap();
+        assert_eq!(blocked_info.name(), "ListBlocked");
+        assert_eq!(blocked_info.state_name(), "Blocked");
+    }
+
+    #[test]
+    fn list_tasks_stops_at_buffer_length() {
+        let mut kernel = Kernel::new(&KCONFIG);
+        kernel.spawn("First", 0).unwrap();
+        kernel.spawn("Second", 0).unwrap();
+        let mut buf = [TaskInfo::empty(); 1];
+
+        assert_eq!(kernel.list_tasks(&mut buf), 1);
+    }
+
+    #[test]
+    fn system_time_is_monotonically_non_decreasing() {
+        let kernel = Kernel::new(&KCONFIG);
+        let first = kernel.get_system_time();
+        let second = kernel.get_system_time();
+        assert!(second >= first);
+    }
+}
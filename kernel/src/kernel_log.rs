@@ -0,0 +1,107 @@
+use crate::kernel_cell::KernelCell;
+
+pub const KERNEL_LOG_CAPACITY: usize = 16 * 1024;
+
+static KERNEL_LOG: KernelCell<KernelLog<KERNEL_LOG_CAPACITY>> = KernelCell::new(KernelLog::new());
+
+pub(crate) fn append(s: &str) {
+    KERNEL_LOG.borrow_mut().append(s.as_bytes());
+}
+
+pub fn read(buf: &mut [u8]) -> usize {
+    KERNEL_LOG.borrow().read(buf)
+}
+
+pub struct KernelLog<const N: usize> {
+    bytes: [u8; N],
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> KernelLog<N> {
+    pub const fn new() -> Self {
+        KernelLog { bytes: [0; N], start: 0, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn append(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if self.len < N {
+                self.bytes[(self.start + self.len) % N] = byte;
+                self.len += 1;
+            } else {
+                self.bytes[self.start] = byte;
+                self.start = (self.start + 1) % N;
+            }
+        }
+    }
+
+    pub fn read(&self, buf: &mut [u8]) -> usize {
+        let count = self.len.min(buf.len());
+        let first = self.start + self.len - count;
+        for (i, slot) in buf[..count].iter_mut().enumerate() {
+            *slot = self.bytes[(first + i) % N];
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents<const N: usize>(log: &KernelLog<N>) -> alloc::vec::Vec<u8> {
+        let mut buf = alloc::vec![0u8; N];
+        let count = log.read(&mut buf);
+        buf.truncate(count);
+        buf
+    }
+
+    #[test]
+    fn new_log_is_empty() {
+        let log: KernelLog<8> = KernelLog::new();
+
+        assert!(log.is_empty());
+        assert_eq!(log.read(&mut [0u8; 8]), 0);
+    }
+
+    #[test]
+    fn append_retains_bytes_in_order_below_capacity() {
+        let mut log: KernelLog<8> = KernelLog::new();
+
+        log.append(b"abc");
+        log.append(b"de");
+
+        assert_eq!(contents(&log), b"abcde");
+    }
+
+    #[test]
+    fn append_drops_oldest_bytes_when_full() {
+        let mut log: KernelLog<8> = KernelLog::new();
+
+        log.append(b"first line\n");
+        log.append(b"next\n");
+
+        assert_eq!(log.len(), 8);
+        assert_eq!(contents(&log), b"ne\nnext\n");
+    }
+
+    #[test]
+    fn read_into_small_buffer_returns_most_recent_bytes() {
+        let mut log: KernelLog<8> = KernelLog::new();
+        log.append(b"0123456789");
+
+        let mut buf = [0u8; 3];
+        let count = log.read(&mut buf);
+
+        assert_eq!(count, 3);
+        assert_eq!(&buf, b"789");
+    }
+}
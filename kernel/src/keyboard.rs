@@ -5,16 +5,85 @@ use alloc::fmt::{Display, Formatter};
 use core::any::Any;
 use lazy_static::lazy_static;
 
+const KEYBOARD_BUFFER_CAPACITY: usize = 64;
+
 lazy_static! {
-    static ref KEYBOARD_BUFFER: KernelCell<VecDeque<char>> = KernelCell::new(VecDeque::new());
+    static ref KEYBOARD_BUFFER: KernelCell<KeyboardBuffer<char>> = KernelCell::new(KeyboardBuffer::new());
+    static ref SCANCODE_BUFFER: KernelCell<KeyboardBuffer<u8>> = KernelCell::new(KeyboardBuffer::new());
+    static ref KEYBOARD_DECODER: KernelCell<KeyboardDecoder> = KernelCell::new(KeyboardDecoder::new());
+}
+
+pub fn push_key(c: char) -> bool {
+    KEYBOARD_BUFFER.borrow_mut().push(c)
 }
 
-pub fn push_key(c: char) {
-    KEYBOARD_BUFFER.borrow_mut().push_back(c);
+pub fn push_scancode(scancode: u8) {
+    SCANCODE_BUFFER.borrow_mut().push_evicting(scancode);
+    let decoded = KEYBOARD_DECODER.borrow_mut().decode(scancode);
+    if let Some(c) = decoded {
+        push_key(c);
+    }
 }
 
 pub fn pop_key() -> Option<char> {
-    KEYBOARD_BUFFER.borrow_mut().pop_front()
+    KEYBOARD_BUFFER.borrow_mut().pop()
+}
+
+pub fn pop_keys(buf: &mut [char]) -> usize {
+    KEYBOARD_BUFFER.borrow_mut().pop_into(buf)
+}
+
+pub fn peek_key() -> Option<char> {
+    KEYBOARD_BUFFER.borrow().peek()
+}
+
+pub fn pop_scancode() -> Option<u8> {
+    SCANCODE_BUFFER.borrow_mut().pop()
+}
+
+struct KeyboardBuffer<T> {
+    keys: VecDeque<T>,
+}
+
+impl<T: Copy> KeyboardBuffer<T> {
+    fn new() -> Self {
+        Self { keys: VecDeque::with_capacity(KEYBOARD_BUFFER_CAPACITY) }
+    }
+
+    fn push(&mut self, c: T) -> bool {
+        if self.keys.len() >= KEYBOARD_BUFFER_CAPACITY {
+            return false;
+        }
+        self.keys.push_back(c);
+        true
+    }
+
+    fn push_evicting(&mut self, c: T) {
+        if self.keys.len() >= KEYBOARD_BUFFER_CAPACITY {
+            self.keys.pop_front();
+        }
+        self.keys.push_back(c);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.keys.pop_front()
+    }
+
+    fn pop_into(&mut self, buf: &mut [T]) -> usize {
+        let count = self.keys.len().min(buf.len());
+        for (slot, key) in buf.iter_mut().zip(self.keys.drain(..count)) {
+            *slot = key;
+        }
+        count
+    }
+
+    fn peek(&self) -> Option<T> {
+        self.keys.front().copied()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
 }
 
 pub struct KeyboardFuture {}
@@ -35,6 +104,84 @@ impl Future for KeyboardFuture {
     }
 }
 
+pub struct KeyboardDecoder {
+    left_shift: bool,
+    right_shift: bool,
+    caps_lock: bool,
+}
+
+impl KeyboardDecoder {
+    pub const fn new() -> Self {
+        Self {
+            left_shift: false,
+            right_shift: false,
+            caps_lock: false,
+        }
+    }
+
+    pub fn decode(&mut self, scancode: u8) -> Option<char> {
+        let released = scancode & 0x80 != 0;
+        let key = Key::from_scancode_set1(scancode).ok()?;
+        match key {
+            Key::LeftShift => {
+                self.left_shift = !released;
+                None
+            }
+            Key::RightShift => {
+                self.right_shift = !released;
+                None
+            }
+            Key::CapsLock => {
+                if !released {
+                    self.caps_lock = !self.caps_lock;
+                }
+                None
+            }
+            _ if released => None,
+            _ => KeyboardEvent::from_key(key).char.map(|c| self.apply_modifiers(c)),
+        }
+    }
+
+    fn apply_modifiers(&self, c: char) -> char {
+        let shift = self.left_shift || self.right_shift;
+        if c.is_ascii_lowercase() {
+            return if shift != self.caps_lock { c.to_ascii_uppercase() } else { c };
+        }
+        if !shift {
+            return c;
+        }
+        match c {
+            '1' => '!',
+            '2' => '@',
+            '3' => '#',
+            '4' => '$',
+            '5' => '%',
+            '6' => '^',
+            '7' => '&',
+            '8' => '*',
+            '9' => '(',
+            '0' => ')',
+            '-' => '_',
+            '=' => '+',
+            '[' => '{',
+            ']' => '}',
+            ';' => ':',
+            '\'' => '"',
+            '\\' => '|',
+            ',' => '<',
+            '.' => '>',
+            '/' => '?',
+            other => other,
+        }
+    }
+}
+
+impl Default for KeyboardDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug)]
 pub enum Key {
     Escape,
@@ -324,3 +471,183 @@ impl Display for KeyboardEvent {
         write!(f, "KeyboardEvent: {}", char)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const LEFT_SHIFT: u8 = 0x2A;
+    const RIGHT_SHIFT: u8 = 0x36;
+    const CAPS_LOCK: u8 = 0x3A;
+    const KEY_A: u8 = 0x1E;
+    const KEY_1: u8 = 0x02;
+    const KEY_SLASH: u8 = 0x35;
+    const BREAK: u8 = 0x80;
+
+    #[test]
+    fn peek_returns_front_without_consuming() {
+        let mut buffer = KeyboardBuffer::new();
+        buffer.push('a');
+        buffer.push('b');
+
+        assert_eq!(buffer.peek(), Some('a'));
+        assert_eq!(buffer.peek(), Some('a'));
+        assert_eq!(buffer.pop(), Some('a'));
+        assert_eq!(buffer.peek(), Some('b'));
+    }
+
+    #[test]
+    fn push_rejects_newest_key_when_full() {
+        let mut buffer = KeyboardBuffer::new();
+        for _ in 0..KEYBOARD_BUFFER_CAPACITY {
+            assert!(buffer.push('a'));
+        }
+
+        assert!(!buffer.push('b'));
+        assert_eq!(buffer.keys.len(), KEYBOARD_BUFFER_CAPACITY);
+        assert!(buffer.keys.iter().all(|&c| c == 'a'));
+    }
+
+    #[test]
+    fn push_evicting_drops_oldest_entry_when_full() {
+        let mut buffer = KeyboardBuffer::new();
+        for scancode in 0..KEYBOARD_BUFFER_CAPACITY as u8 {
+            buffer.push_evicting(scancode);
+        }
+
+        buffer.push_evicting(0xFF);
+
+        assert_eq!(buffer.keys.len(), KEYBOARD_BUFFER_CAPACITY);
+        assert_eq!(buffer.peek(), Some(1));
+        assert_eq!(buffer.keys.back(), Some(&0xFF));
+    }
+
+    #[test]
+    fn push_accepts_again_after_pop_frees_space() {
+        let mut buffer = KeyboardBuffer::new();
+        for _ in 0..KEYBOARD_BUFFER_CAPACITY {
+            buffer.push('a');
+        }
+
+        buffer.pop();
+
+        assert!(buffer.push('b'));
+        assert_eq!(buffer.keys.back(), Some(&'b'));
+    }
+
+    #[test]
+    fn pop_into_drains_queued_keys_in_order() {
+        let mut buffer = KeyboardBuffer::new();
+        for c in ['a', 'b', 'c'] {
+            buffer.push(c);
+        }
+
+        let mut keys = ['\0'; 8];
+        let count = buffer.pop_into(&mut keys);
+
+        assert_eq!(count, 3);
+        assert_eq!(&keys[..count], ['a', 'b', 'c']);
+        assert_eq!(buffer.pop(), None);
+    }
+
+    #[test]
+    fn pop_into_leaves_keys_that_do_not_fit() {
+        let mut buffer = KeyboardBuffer::new();
+        for c in ['a', 'b', 'c'] {
+            buffer.push(c);
+        }
+
+        let mut keys = ['\0'; 2];
+
+        assert_eq!(buffer.pop_into(&mut keys), 2);
+        assert_eq!(keys, ['a', 'b']);
+        assert_eq!(buffer.pop(), Some('c'));
+    }
+
+    #[test]
+    fn pop_into_returns_zero_when_empty() {
+        let mut buffer: KeyboardBuffer<char> = KeyboardBuffer::new();
+
+        assert_eq!(buffer.pop_into(&mut ['\0'; 4]), 0);
+    }
+
+    #[test]
+    fn peek_returns_none_when_empty() {
+        let buffer: KeyboardBuffer<char> = KeyboardBuffer::new();
+        assert_eq!(buffer.peek(), None);
+    }
+
+    #[test]
+    fn push_scancode_exposes_raw_scancodes_and_decoded_chars() {
+        push_scancode(KEY_A);
+        push_scancode(KEY_A | BREAK);
+
+        assert_eq!(pop_scancode(), Some(KEY_A));
+        assert_eq!(pop_scancode(), Some(KEY_A | BREAK));
+        assert_eq!(pop_scancode(), None);
+
+        assert_eq!(pop_key(), Some('a'));
+        assert_eq!(pop_key(), None);
+    }
+
+    #[test]
+    fn peek_key_leaves_the_key_for_the_next_pop() {
+        push_key('x');
+
+        assert_eq!(peek_key(), Some('x'));
+        assert_eq!(pop_key(), Some('x'));
+        assert_eq!(peek_key(), None);
+    }
+
+    fn feed(decoder: &mut KeyboardDecoder, scancodes: &[u8]) -> Vec<char> {
+        scancodes.iter().filter_map(|&scancode| decoder.decode(scancode)).collect()
+    }
+
+    #[test]
+    fn decoder_produces_lowercase_without_modifiers() {
+        let mut decoder = KeyboardDecoder::new();
+        assert_eq!(feed(&mut decoder, &[KEY_A, KEY_A | BREAK]), ['a']);
+    }
+
+    #[test]
+    fn decoder_produces_uppercase_while_shift_is_held() {
+        let mut decoder = KeyboardDecoder::new();
+        let chars = feed(
+            &mut decoder,
+            &[LEFT_SHIFT, KEY_A, KEY_A | BREAK, LEFT_SHIFT | BREAK, KEY_A, KEY_A | BREAK],
+        );
+        assert_eq!(chars, ['A', 'a']);
+    }
+
+    #[test]
+    fn decoder_shifts_digits_and_symbols() {
+        let mut decoder = KeyboardDecoder::new();
+        let chars = feed(&mut decoder, &[RIGHT_SHIFT, KEY_1, KEY_SLASH, RIGHT_SHIFT | BREAK, KEY_1]);
+        assert_eq!(chars, ['!', '?', '1']);
+    }
+
+    #[test]
+    fn decoder_toggles_caps_lock_on_make_only() {
+        let mut decoder = KeyboardDecoder::new();
+        let chars = feed(
+            &mut decoder,
+            &[CAPS_LOCK, CAPS_LOCK | BREAK, KEY_A, KEY_1, CAPS_LOCK, CAPS_LOCK | BREAK, KEY_A],
+        );
+        assert_eq!(chars, ['A', '1', 'a']);
+    }
+
+    #[test]
+    fn decoder_shift_inverts_caps_lock_for_letters() {
+        let mut decoder = KeyboardDecoder::new();
+        let chars = feed(&mut decoder, &[CAPS_LOCK, LEFT_SHIFT, KEY_A, KEY_1]);
+        assert_eq!(chars, ['a', '!']);
+    }
+
+    #[test]
+    fn decoder_keeps_shift_while_other_shift_is_held() {
+        let mut decoder = KeyboardDecoder::new();
+        let chars = feed(&mut decoder, &[LEFT_SHIFT, RIGHT_SHIFT, LEFT_SHIFT | BREAK, KEY_A]);
+        assert_eq!(chars, ['A']);
+    }
+}
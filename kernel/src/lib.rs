@@ -14,6 +14,7 @@ pub mod ipc;
 pub mod kconfig;
 pub mod kernel;
 pub(crate) mod kernel_cell;
+pub mod kernel_log;
 pub(crate) mod kernel_services;
 mod keyboard;
 pub mod memory;
@@ -5,12 +5,19 @@ pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
 const BITS_PER_WORD: usize = usize::BITS as usize;
 const METADATA_ALIGNMENT: usize = 16;
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum ChunkOwner {
     Kernel,
     Task(TaskHandle),
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum AllocationPolicy {
+    #[default]
+    FirstFit,
+    LowestAddress,
+}
+
 pub struct Allocation {
     pub ptr: *mut u8,
     pub chunk_count: usize,
@@ -37,6 +44,7 @@ pub struct BitmapChunkAllocator {
     total_chunks: usize,
     chunk_size: usize,
     owner: *mut ChunkOwner,
+    policy: AllocationPolicy,
 }
 
 fn align_up(value: usize, alignment: usize) -> usize {
@@ -112,9 +120,15 @@ impl BitmapChunkAllocator {
             total_chunks,
             chunk_size,
             owner: owner_ptr,
+            policy: AllocationPolicy::default(),
         }
     }
 
+    pub fn with_policy(mut self, policy: AllocationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     pub fn used_chunks(&self) -> usize {
         let mut count = 0;
         for r in 0..self.region_count {
@@ -134,6 +148,29 @@ impl BitmapChunkAllocator {
         self.total_chunks - self.used_chunks()
     }
 
+    pub fn reset(&mut self) {
+        self.mark_bits(0, self.total_chunks, false);
+        self.write_owner(0, self.total_chunks, ChunkOwner::Kernel);
+    }
+
+    pub fn largest_free_run(&self) -> usize {
+        let mut largest = 0;
+        for r in 0..self.region_count {
+            let region = self.region(r);
+            let offset = region.bitmap_offset;
+            let mut run = 0;
+            for i in offset..offset + region.chunk_count {
+                if self.is_bit_set(i) {
+                    run = 0;
+                } else {
+                    run += 1;
+                    largest = largest.max(run);
+                }
+            }
+        }
+        largest
+    }
+
     fn region(&self, index: usize) -> &Region {
         // Safety: index is always < self.region_count, which was bounded
         // by the number of regions written during construction.
@@ -160,6 +197,29 @@ impl BitmapChunkAllocator {
         None
     }
 
+    fn find_aligned_free_run(
+        &self,
+        base: usize,
+        bitmap_offset: usize,
+        region_chunks: usize,
+        needed: usize,
+        align: usize,
+    ) -> Option<usize> {
+        let mut chunk = 0;
+        while chunk + needed <= region_chunks {
+            if (base + chunk * self.chunk_size) & (align - 1) != 0 {
+                chunk += 1;
+                continue;
+            }
+            let start = bitmap_offset + chunk;
+            match (start..start + needed).find(|&bit_index| self.is_bit_set(bit_index)) {
+                Some(used) => chunk = used - bitmap_offset + 1,
+                None => return Some(start),
+            }
+        }
+        None
+    }
+
     fn is_bit_set(&self, bit_index: usize) -> bool {
         let word = bit_index / BITS_PER_WORD;
         let bit = bit_index % BITS_PER_WORD;
@@ -189,15 +249,48 @@ impl BitmapChunkAllocator {
         }
     }
 
-    pub fn deallocate_by_owner(&mut self, task: TaskHandle) {
+    pub fn deallocate_by_owner(&mut self, task: TaskHandle) -> usize {
+        let mut freed = 0;
         for i in 0..self.total_chunks {
             // Safety: i < total_chunks bounds both the owner and bitmap arrays.
             let owned_by_task = unsafe { *self.owner.add(i) } == ChunkOwner::Task(task);
             if owned_by_task && self.is_bit_set(i) {
                 self.mark_bits(i, 1, false);
                 unsafe { *self.owner.add(i) = ChunkOwner::Kernel; }
+                freed += 1;
             }
         }
+        freed
+    }
+
+    pub fn chunks_owned_by(&self, task: TaskHandle) -> usize {
+        let mut count = 0;
+        for i in 0..self.total_chunks {
+            // Safety: i < total_chunks bounds both the owner and bitmap arrays.
+            let owned_by_task = unsafe { *self.owner.add(i) } == ChunkOwner::Task(task);
+            if owned_by_task && self.is_bit_set(i) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    pub fn owner_of(&self, ptr: *mut u8) -> Option<ChunkOwner> {
+        let addr = ptr as usize;
+        for r in 0..self.region_count {
+            let region = self.region(r);
+            let base = region.base;
+            let region_end = base + region.chunk_count * self.chunk_size;
+            if addr >= base && addr < region_end {
+                let bit = region.bitmap_offset + (addr - base) / self.chunk_size;
+                if !self.is_bit_set(bit) {
+                    return None;
+                }
+                // Safety: bit < total_chunks, which bounds the owner array.
+                return Some(unsafe { *self.owner.add(bit) });
+            }
+        }
+        None
     }
 
     pub fn transfer_to_task(&mut self, ptr: *mut u8, chunk_count: usize, task: TaskHandle) {
@@ -222,11 +315,8 @@ impl ChunkAllocator for BitmapChunkAllocator {
         if bytes == 0 {
             return None;
         }
-        assert!(
-            self.chunk_size >= layout.align(),
-            "chunk_size must be >= layout alignment"
-        );
         let chunk_count = (bytes + self.chunk_size - 1) / self.chunk_size;
+        let mut selected: Option<(usize, usize)> = None;
         for r in 0..self.region_count {
             let region = self.region(r);
             let base = region.base;
@@ -235,19 +325,29 @@ impl ChunkAllocator for BitmapChunkAllocator {
             if region_chunks < chunk_count {
                 continue;
             }
-            if let Some(start) = self.find_free_run(bitmap_offset, region_chunks, chunk_count) {
-                self.mark_bits(start, chunk_count, true);
-                self.write_owner(start, chunk_count, owner);
-                let chunk_in_region = start - bitmap_offset;
-                let addr = base + chunk_in_region * self.chunk_size;
-                return Some(Allocation {
-                    ptr: addr as *mut u8,
-                    chunk_count,
-                    chunk_size: self.chunk_size,
-                });
+            let run = if layout.align() <= self.chunk_size {
+                self.find_free_run(bitmap_offset, region_chunks, chunk_count)
+            } else {
+                self.find_aligned_free_run(base, bitmap_offset, region_chunks, chunk_count, layout.align())
+            };
+            if let Some(start) = run {
+                let addr = base + (start - bitmap_offset) * self.chunk_size;
+                if selected.is_none_or(|(_, best)| addr < best) {
+                    selected = Some((start, addr));
+                }
+                if self.policy == AllocationPolicy::FirstFit {
+                    break;
+                }
             }
         }
-        None
+        let (start, addr) = selected?;
+        self.mark_bits(start, chunk_count, true);
+        self.write_owner(start, chunk_count, owner);
+        Some(Allocation {
+            ptr: addr as *mut u8,
+            chunk_count,
+            chunk_size: self.chunk_size,
+        })
     }
 
     fn deallocate(&mut self, ptr: *mut u8, chunk_count: usize) {
@@ -582,6 +682,89 @@ mod tests {
         assert_eq!(allocator.free_chunks(), total - 3);
     }
 
+    fn allocate_every_chunk(allocator: &mut BitmapChunkAllocator) -> Vec<*mut u8> {
+        let layout = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let mut ptrs = Vec::new();
+        while let Some(allocation) = allocator.allocate(layout, ChunkOwner::Kernel) {
+            ptrs.push(allocation.ptr);
+        }
+        ptrs
+    }
+
+    #[test]
+    fn reset_frees_every_chunk() {
+        let mut mem = vec![0u8; 8 * DEFAULT_CHUNK_SIZE];
+        let mut allocator = BitmapChunkAllocator::new(&[(mem.as_mut_ptr() as usize, mem.len())]);
+        let total = allocator.free_chunks();
+        allocate_every_chunk(&mut allocator);
+        assert_eq!(allocator.free_chunks(), 0);
+
+        allocator.reset();
+
+        assert_eq!(allocator.free_chunks(), total);
+        assert_eq!(allocator.largest_free_run(), total);
+    }
+
+    #[test]
+    fn reset_returns_task_chunks_to_the_kernel() {
+        let mut mem = vec![0u8; 8 * DEFAULT_CHUNK_SIZE];
+        let task = TaskHandle::new(1, 1);
+        let mut allocator = BitmapChunkAllocator::new(&[(mem.as_mut_ptr() as usize, mem.len())]);
+        let layout = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        allocator.allocate(layout, ChunkOwner::Task(task)).unwrap();
+
+        allocator.reset();
+
+        assert_eq!(allocator.chunks_owned_by(task), 0);
+        assert_eq!(allocator.used_chunks(), 0);
+    }
+
+    #[test]
+    fn largest_free_run_of_empty_region_is_all_free_chunks() {
+        let mut mem = vec![0u8; 8 * DEFAULT_CHUNK_SIZE];
+        let allocator = BitmapChunkAllocator::new(&[(mem.as_mut_ptr() as usize, mem.len())]);
+
+        assert_eq!(allocator.largest_free_run(), allocator.free_chunks());
+    }
+
+    #[test]
+    fn largest_free_run_is_zero_when_full() {
+        let mut mem = vec![0u8; 8 * DEFAULT_CHUNK_SIZE];
+        let mut allocator = BitmapChunkAllocator::new(&[(mem.as_mut_ptr() as usize, mem.len())]);
+
+        allocate_every_chunk(&mut allocator);
+
+        assert_eq!(allocator.largest_free_run(), 0);
+    }
+
+    #[test]
+    fn largest_free_run_ignores_fragmented_chunks() {
+        let mut mem = vec![0u8; 8 * DEFAULT_CHUNK_SIZE];
+        let mut allocator = BitmapChunkAllocator::new(&[(mem.as_mut_ptr() as usize, mem.len())]);
+        let ptrs = allocate_every_chunk(&mut allocator);
+        assert!(ptrs.len() >= 4);
+
+        allocator.deallocate(ptrs[0], 1);
+        allocator.deallocate(ptrs[2], 1);
+        allocator.deallocate(ptrs[3], 1);
+
+        assert_eq!(allocator.free_chunks(), 3);
+        assert_eq!(allocator.largest_free_run(), 2);
+    }
+
+    #[test]
+    fn largest_free_run_does_not_span_regions() {
+        let mut mem1 = vec![0u8; 4 * DEFAULT_CHUNK_SIZE];
+        let mut mem2 = vec![0u8; 3 * DEFAULT_CHUNK_SIZE];
+        let allocator = BitmapChunkAllocator::new(&[
+            (mem1.as_mut_ptr() as usize, mem1.len()),
+            (mem2.as_mut_ptr() as usize, mem2.len()),
+        ]);
+        let first_region_chunks = allocator.free_chunks() - 3;
+
+        assert_eq!(allocator.largest_free_run(), first_region_chunks.max(3));
+    }
+
     #[test]
     fn new_accepts_more_than_32_ranges() {
         let range_count = 40;
@@ -683,20 +866,55 @@ mod tests {
         assert!(!alloc.ptr.is_null());
     }
 
+    fn misaligned_ranges(memory: &mut Vec<u8>, align: usize) -> [(usize, usize); 2] {
+        let aligned = align_up(memory.as_mut_ptr() as usize, align);
+        [
+            (aligned + 4096, DEFAULT_CHUNK_SIZE + 4096),
+            (aligned + 3 * DEFAULT_CHUNK_SIZE, 8 * DEFAULT_CHUNK_SIZE),
+        ]
+    }
+
     #[test]
-    #[should_panic(expected = "chunk_size must be >= layout alignment")]
-    fn allocate_panics_when_alignment_exceeds_chunk_size() {
-        let chunk_size: usize = 4096;
-        let mut memory = vec![0u8; 10 * chunk_size];
-        let base = memory.as_mut_ptr() as usize;
+    fn allocate_honours_alignment_larger_than_chunk_size() {
+        let align = 2 * DEFAULT_CHUNK_SIZE;
+        let mut memory = vec![0u8; 14 * DEFAULT_CHUNK_SIZE];
+        let ranges = misaligned_ranges(&mut memory, align);
+        let mut allocator = BitmapChunkAllocator::new(&ranges);
 
-        let mut allocator = BitmapChunkAllocator::with_chunk_size(
-            chunk_size,
-            &[(base, memory.len())],
-        );
+        let layout = Layout::from_size_align(DEFAULT_CHUNK_SIZE, align).unwrap();
+        let alloc = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
 
-        let layout = Layout::from_size_align(chunk_size, chunk_size * 2).unwrap();
-        allocator.allocate(layout, ChunkOwner::Kernel);
+        assert_eq!(alloc.ptr as usize % align, 0);
+        assert_eq!(alloc.ptr as usize, ranges[1].0 + DEFAULT_CHUNK_SIZE);
+        assert_eq!(allocator.used_chunks(), 1);
+    }
+
+    #[test]
+    fn aligned_allocation_skips_runs_interrupted_by_used_chunks() {
+        let align = 2 * DEFAULT_CHUNK_SIZE;
+        let mut memory = vec![0u8; 14 * DEFAULT_CHUNK_SIZE];
+        let ranges = misaligned_ranges(&mut memory, align);
+        let mut allocator = BitmapChunkAllocator::new(&ranges);
+
+        let layout = Layout::from_size_align(2 * DEFAULT_CHUNK_SIZE, align).unwrap();
+        let first = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
+        let second = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
+
+        assert_eq!(first.ptr as usize % align, 0);
+        assert_eq!(second.ptr as usize % align, 0);
+        assert_eq!(second.ptr as usize, first.ptr as usize + 2 * DEFAULT_CHUNK_SIZE);
+    }
+
+    #[test]
+    fn aligned_allocation_fails_when_no_aligned_run_fits() {
+        let align = 2 * DEFAULT_CHUNK_SIZE;
+        let mut memory = vec![0u8; 14 * DEFAULT_CHUNK_SIZE];
+        let ranges = misaligned_ranges(&mut memory, align);
+        let mut allocator = BitmapChunkAllocator::new(&ranges);
+
+        let layout = Layout::from_size_align(8 * DEFAULT_CHUNK_SIZE, align).unwrap();
+
+        assert!(allocator.allocate(layout, ChunkOwner::Kernel).is_none());
     }
 
     #[test]
@@ -719,6 +937,23 @@ mod tests {
         assert_eq!(allocator.free_chunks(), total - 1);
     }
 
+    #[test]
+    fn deallocate_by_owner_returns_number_of_chunks_freed() {
+        let mut memory = vec![0u8; 5 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+        let task = TaskHandle::new(1, 1);
+
+        let mut allocator = BitmapChunkAllocator::new(&[(base, memory.len())]);
+        let chunk = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let two_chunks = Layout::from_size_align(2 * DEFAULT_CHUNK_SIZE, 1).unwrap();
+        allocator.allocate(two_chunks, ChunkOwner::Task(task));
+        allocator.allocate(chunk, ChunkOwner::Kernel);
+        allocator.allocate(chunk, ChunkOwner::Task(task));
+
+        assert_eq!(allocator.deallocate_by_owner(task), 3);
+        assert_eq!(allocator.deallocate_by_owner(task), 0);
+    }
+
     #[test]
     fn deallocate_by_owner_does_not_free_kernel_chunks() {
         let mut memory = vec![0u8; 5 * DEFAULT_CHUNK_SIZE];
@@ -753,6 +988,42 @@ mod tests {
         assert_eq!(allocator.used_chunks(), 1);
     }
 
+    #[test]
+    fn chunks_owned_by_counts_each_task_separately() {
+        let mut memory = vec![0u8; 8 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+        let task_a = TaskHandle::new(1, 1);
+        let task_b = TaskHandle::new(2, 1);
+
+        let mut allocator = BitmapChunkAllocator::new(&[(base, memory.len())]);
+        let chunk = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let two_chunks = Layout::from_size_align(2 * DEFAULT_CHUNK_SIZE, 1).unwrap();
+        allocator.allocate(two_chunks, ChunkOwner::Task(task_a));
+        allocator.allocate(chunk, ChunkOwner::Task(task_a));
+        allocator.allocate(chunk, ChunkOwner::Task(task_b));
+        allocator.allocate(chunk, ChunkOwner::Kernel);
+
+        assert_eq!(allocator.chunks_owned_by(task_a), 3);
+        assert_eq!(allocator.chunks_owned_by(task_b), 1);
+        assert_eq!(allocator.chunks_owned_by(TaskHandle::new(3, 1)), 0);
+        assert_eq!(allocator.used_chunks(), 5);
+    }
+
+    #[test]
+    fn chunks_owned_by_drops_to_zero_after_deallocate_by_owner() {
+        let mut memory = vec![0u8; 5 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+        let task = TaskHandle::new(1, 1);
+
+        let mut allocator = BitmapChunkAllocator::new(&[(base, memory.len())]);
+        let layout = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        allocator.allocate(layout, ChunkOwner::Task(task));
+
+        allocator.deallocate_by_owner(task);
+
+        assert_eq!(allocator.chunks_owned_by(task), 0);
+    }
+
     #[test]
     fn metadata_stored_in_second_range_when_first_too_small() {
         // One chunk is too small: overhead(96) + chunk(65536) = 65632 > 65536
@@ -797,6 +1068,38 @@ mod tests {
         BitmapChunkAllocator::new(&ranges);
     }
 
+    #[test]
+    fn owner_of_reports_owner_of_allocated_chunks() {
+        let mut memory = vec![0u8; 5 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+        let task = TaskHandle::new(1, 1);
+
+        let mut allocator = BitmapChunkAllocator::new(&[(base, memory.len())]);
+        let layout = Layout::from_size_align(2 * DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let task_alloc = allocator.allocate(layout, ChunkOwner::Task(task)).unwrap();
+        let kernel_alloc = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
+
+        let inside_second_task_chunk = unsafe { task_alloc.ptr.add(DEFAULT_CHUNK_SIZE + 8) };
+        assert_eq!(allocator.owner_of(task_alloc.ptr), Some(ChunkOwner::Task(task)));
+        assert_eq!(allocator.owner_of(inside_second_task_chunk), Some(ChunkOwner::Task(task)));
+        assert_eq!(allocator.owner_of(kernel_alloc.ptr), Some(ChunkOwner::Kernel));
+    }
+
+    #[test]
+    fn owner_of_is_none_for_free_chunks_and_foreign_pointers() {
+        let mut memory = vec![0u8; 5 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+
+        let mut allocator = BitmapChunkAllocator::new(&[(base, memory.len())]);
+        let layout = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let alloc = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
+        allocator.deallocate(alloc.ptr, alloc.chunk_count);
+
+        assert_eq!(allocator.owner_of(alloc.ptr), None);
+        assert_eq!(allocator.owner_of((base + memory.len()) as *mut u8), None);
+        assert_eq!(allocator.owner_of(core::ptr::null_mut()), None);
+    }
+
     #[test]
     fn transfer_to_task_moves_kernel_chunks_to_task() {
         let mut memory = vec![0u8; 5 * DEFAULT_CHUNK_SIZE];
@@ -814,6 +1117,61 @@ mod tests {
         assert_eq!(allocator.free_chunks(), total);
     }
 
+    #[test]
+    fn first_fit_policy_allocates_from_first_listed_region() {
+        let mut memory = vec![0u8; 10 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+        let high = base + 5 * DEFAULT_CHUNK_SIZE;
+
+        let mut allocator = BitmapChunkAllocator::new(&[
+            (high, 5 * DEFAULT_CHUNK_SIZE),
+            (base, 5 * DEFAULT_CHUNK_SIZE),
+        ]);
+        let layout = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let alloc = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
+
+        assert!(alloc.ptr as usize >= high);
+    }
+
+    #[test]
+    fn lowest_address_policy_allocates_lowest_free_chunk_across_regions() {
+        let mut memory = vec![0u8; 10 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+        let high = base + 5 * DEFAULT_CHUNK_SIZE;
+
+        let mut allocator = BitmapChunkAllocator::new(&[
+            (high, 5 * DEFAULT_CHUNK_SIZE),
+            (base, 5 * DEFAULT_CHUNK_SIZE),
+        ]).with_policy(AllocationPolicy::LowestAddress);
+        let layout = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let first = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
+        let second = allocator.allocate(layout, ChunkOwner::Kernel).unwrap();
+
+        assert_eq!(first.ptr as usize, base);
+        assert_eq!(second.ptr as usize, base + DEFAULT_CHUNK_SIZE);
+    }
+
+    #[test]
+    fn lowest_address_policy_skips_regions_without_a_fitting_run() {
+        let mut memory = vec![0u8; 10 * DEFAULT_CHUNK_SIZE];
+        let base = memory.as_mut_ptr() as usize;
+        let high = base + 5 * DEFAULT_CHUNK_SIZE;
+
+        let mut allocator = BitmapChunkAllocator::new(&[
+            (high, 5 * DEFAULT_CHUNK_SIZE),
+            (base, 5 * DEFAULT_CHUNK_SIZE),
+        ]).with_policy(AllocationPolicy::LowestAddress);
+        let chunk = Layout::from_size_align(DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let low_chunks: Vec<_> = (0..5).map(|_| allocator.allocate(chunk, ChunkOwner::Kernel).unwrap()).collect();
+        allocator.deallocate(low_chunks[1].ptr, 1);
+        allocator.deallocate(low_chunks[3].ptr, 1);
+
+        let two_chunks = Layout::from_size_align(2 * DEFAULT_CHUNK_SIZE, 1).unwrap();
+        let alloc = allocator.allocate(two_chunks, ChunkOwner::Kernel).unwrap();
+
+        assert!(alloc.ptr as usize >= high);
+    }
+
     #[test]
     fn bitmap_allocator_implements_chunk_allocator_trait() {
         let mut memory = vec![0u8; 5 * DEFAULT_CHUNK_SIZE];
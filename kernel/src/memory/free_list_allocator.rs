@@ -10,6 +10,7 @@ struct FreeBlock {
 
 struct AllocHeader {
     size: usize,
+    requested: usize,
     owner: BlockOwner,
     next: *mut AllocHeader,
 }
@@ -25,11 +26,18 @@ const BLOCK_ALIGN: usize = core::mem::align_of::<FreeBlock>();
 const ALLOC_HDR: usize = core::mem::size_of::<AllocHeader>();
 
 #[derive(Debug, PartialEq)]
-pub(crate) enum AllocError {
+pub enum AllocError {
     OutOfMemory,
     AlignmentUnsupported,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreeListStats {
+    pub free_bytes: usize,
+    pub free_blocks: usize,
+    pub largest_free_block: usize,
+}
+
 pub struct FreeListAllocator {
     head: *mut FreeBlock,
     alloc_head: *mut AllocHeader,
@@ -39,6 +47,11 @@ fn align_up(addr: usize, align: usize) -> usize {
     (addr + align - 1) & !(align - 1)
 }
 
+fn required_size(size: usize) -> usize {
+    let usable = align_up(size, BLOCK_ALIGN);
+    (ALLOC_HDR + usable).max(BLOCK_HDR)
+}
+
 impl FreeListAllocator {
     pub fn new(memory_blocks: &MemoryBlocks) -> Self {
         let mut head: *mut FreeBlock = ptr::null_mut();
@@ -59,6 +72,19 @@ impl FreeListAllocator {
         FreeListAllocator { head, alloc_head: ptr::null_mut() }
     }
 
+    pub fn stats(&self) -> FreeListStats {
+        let mut stats = FreeListStats { free_bytes: 0, free_blocks: 0, largest_free_block: 0 };
+        let mut current = self.head;
+        while !current.is_null() {
+            let size = unsafe { (*current).size };
+            stats.free_bytes += size;
+            stats.free_blocks += 1;
+            stats.largest_free_block = stats.largest_free_block.max(size);
+            current = unsafe { (*current).next };
+        }
+        stats
+    }
+
     pub unsafe fn allocate(&mut self, layout: Layout, owner: BlockOwner) -> Result<*mut u8, AllocError> {
         if layout.size() == 0 {
             return Err(AllocError::OutOfMemory);
@@ -67,8 +93,7 @@ impl FreeListAllocator {
             return Err(AllocError::AlignmentUnsupported);
         }
 
-        let usable = align_up(layout.size(), BLOCK_ALIGN);
-        let needed = (ALLOC_HDR + usable).max(BLOCK_HDR);
+        let needed = required_size(layout.size());
 
         let mut prev_next: *mut *mut FreeBlock = &mut self.head;
         let mut current = self.head;
@@ -89,9 +114,7 @@ impl FreeListAllocator {
                     used_size = block_size;
                 }
                 let header = start as *mut AllocHeader;
-                (*header).size = used_size;
-                (*header).owner = owner;
-                (*header).next = self.alloc_head;
+                *header = AllocHeader { size: used_size, requested: layout.size(), owner, next: self.alloc_head };
                 self.alloc_head = header;
                 return Ok((start + ALLOC_HDR) as *mut u8);
             }
@@ -121,22 +144,94 @@ impl FreeListAllocator {
         self.insert_free_block(start, block_size);
     }
 
-    pub unsafe fn deallocate_by_owner(&mut self, task_id: usize) {
+    pub unsafe fn reallocate(&mut self, ptr: *mut u8, old_layout: Layout, new_layout: Layout) -> Result<*mut u8, AllocError> {
+        if new_layout.size() == 0 {
+            return Err(AllocError::OutOfMemory);
+        }
+        if new_layout.align() > BLOCK_ALIGN {
+            return Err(AllocError::AlignmentUnsupported);
+        }
+
+        let start = ptr as usize - ALLOC_HDR;
+        let header = start as *mut AllocHeader;
+        let needed = required_size(new_layout.size());
+        let current_size = unsafe { (*header).size };
+
+        if needed <= current_size {
+            let remaining = current_size - needed;
+            unsafe {
+                if remaining >= BLOCK_HDR {
+                    (*header).size = needed;
+                    self.insert_free_block(start + needed, remaining);
+                }
+                (*header).requested = new_layout.size();
+            }
+            return Ok(ptr);
+        }
+
+        if unsafe { self.grow_in_place(start, current_size, needed) } {
+            unsafe { (*header).requested = new_layout.size() };
+            return Ok(ptr);
+        }
+
+        let owner = unsafe { (*header).owner };
+        let new_ptr = unsafe { self.allocate(new_layout, owner) }?;
+        unsafe {
+            ptr::copy_nonoverlapping(ptr, new_ptr, old_layout.size().min(new_layout.size()));
+            self.deallocate(ptr);
+        }
+        Ok(new_ptr)
+    }
+
+    unsafe fn grow_in_place(&mut self, start: usize, current_size: usize, needed: usize) -> bool {
+        let block_end = start + current_size;
+        let mut prev_next: *mut *mut FreeBlock = &mut self.head;
+        let mut current = self.head;
+        unsafe {
+            while !current.is_null() && (current as usize) < block_end {
+                prev_next = &mut (*current).next;
+                current = (*current).next;
+            }
+            if current as usize != block_end || current_size + (*current).size < needed {
+                return false;
+            }
+
+            let total = current_size + (*current).size;
+            let remaining = total - needed;
+            let header = start as *mut AllocHeader;
+            if remaining >= BLOCK_HDR {
+                let split = (start + needed) as *mut FreeBlock;
+                let next = (*current).next;
+                (*split).size = remaining;
+                (*split).next = next;
+                *prev_next = split;
+                (*header).size = needed;
+            } else {
+                *prev_next = (*current).next;
+                (*header).size = total;
+            }
+        }
+        true
+    }
+
+    pub unsafe fn deallocate_by_owner(&mut self, task_id: usize) -> usize {
         let target = BlockOwner::Task(task_id);
+        let mut released = 0;
         let mut prev_next: *mut *mut AllocHeader = &mut self.alloc_head;
         let mut current = self.alloc_head;
         while !current.is_null() {
             let next = (*current).next;
             if (*current).owner == target {
                 *prev_next = next;
-                let start = current as usize;
-                let block_size = (*current).size;
-                self.insert_free_block(start, block_size);
+                let AllocHeader { size: block_size, requested, .. } = *current;
+                released += requested;
+                self.insert_free_block(current as usize, block_size);
             } else {
                 prev_next = &mut (*current).next;
             }
             current = next;
         }
+        released
     }
 
     unsafe fn insert_free_block(&mut self, start: usize, block_size: usize) {
@@ -191,8 +286,7 @@ mod tests {
     }
 
     fn needed_for(size: usize) -> usize {
-        let usable = align_up(size, BLOCK_ALIGN);
-        (ALLOC_HDR + usable).max(BLOCK_HDR)
+        required_size(size)
     }
 
     #[test]
@@ -373,4 +467,107 @@ mod tests {
             Err(AllocError::OutOfMemory)
         ));
     }
+
+    #[test]
+    fn reallocate_grows_in_place_when_next_block_is_free() {
+        let mut memory = vec![0u8; 4096];
+        let base = memory.as_mut_ptr() as usize;
+        let mut alloc = make_allocator(&[(base, 4096)]);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(256, 8).unwrap();
+        let ptr = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        let grown = unsafe { alloc.reallocate(ptr, small, large) }.unwrap();
+        assert_eq!(grown, ptr);
+        let next = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap() as usize;
+        assert!(next >= grown as usize + 256);
+    }
+
+    #[test]
+    fn reallocate_relocates_and_preserves_data_when_next_block_is_used() {
+        let mut memory = vec![0u8; 4096];
+        let base = memory.as_mut_ptr() as usize;
+        let mut alloc = make_allocator(&[(base, 4096)]);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(256, 8).unwrap();
+        let ptr = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        let _blocker = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        for i in 0..64 {
+            unsafe { *ptr.add(i) = i as u8 };
+        }
+        let grown = unsafe { alloc.reallocate(ptr, small, large) }.unwrap();
+        assert_ne!(grown, ptr);
+        for i in 0..64 {
+            assert_eq!(unsafe { *grown.add(i) }, i as u8);
+        }
+        let reused = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        assert_eq!(reused, ptr);
+    }
+
+    #[test]
+    fn reallocate_shrink_releases_tail_as_free_block() {
+        let region_size = needed_for(256);
+        let mut memory = vec![0u8; region_size];
+        let base = memory.as_mut_ptr() as usize;
+        let mut alloc = make_allocator(&[(base, region_size)]);
+        let large = Layout::from_size_align(256, 8).unwrap();
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let ptr = unsafe { alloc.allocate(large, BlockOwner::Kernel) }.unwrap();
+        assert!(matches!(
+            unsafe { alloc.allocate(small, BlockOwner::Kernel) },
+            Err(AllocError::OutOfMemory)
+        ));
+        let shrunk = unsafe { alloc.reallocate(ptr, large, small) }.unwrap();
+        assert_eq!(shrunk, ptr);
+        let tail = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap() as usize;
+        assert_eq!(tail, base + needed_for(64) + ALLOC_HDR);
+    }
+
+    #[test]
+    fn reallocate_keeps_block_owner_when_relocating() {
+        let mut memory = vec![0u8; 4096];
+        let base = memory.as_mut_ptr() as usize;
+        let mut alloc = make_allocator(&[(base, 4096)]);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(256, 8).unwrap();
+        let ptr = unsafe { alloc.allocate(small, BlockOwner::Task(7)) }.unwrap();
+        let _blocker = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        let grown = unsafe { alloc.reallocate(ptr, small, large) }.unwrap();
+        unsafe { alloc.deallocate_by_owner(7) };
+        let reused = unsafe { alloc.allocate(large, BlockOwner::Kernel) }.unwrap();
+        assert_eq!(reused, grown);
+    }
+
+    #[test]
+    fn stats_reports_single_block_for_fresh_region() {
+        let mut memory = vec![0u8; 4096];
+        let base = memory.as_mut_ptr() as usize;
+        let alloc = make_allocator(&[(base, 4096)]);
+        let stats = alloc.stats();
+        assert_eq!(stats.free_blocks, 1);
+        assert_eq!(stats.free_bytes, 4096);
+        assert_eq!(stats.largest_free_block, 4096);
+    }
+
+    #[test]
+    fn stats_reports_discontiguous_free_blocks() {
+        let mut memory = vec![0u8; 4096];
+        let base = memory.as_mut_ptr() as usize;
+        let mut alloc = make_allocator(&[(base, 4096)]);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(256, 8).unwrap();
+        let a = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        let _b = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        let c = unsafe { alloc.allocate(large, BlockOwner::Kernel) }.unwrap();
+        let _d = unsafe { alloc.allocate(small, BlockOwner::Kernel) }.unwrap();
+        unsafe {
+            alloc.deallocate(a);
+            alloc.deallocate(c);
+        }
+        let used = 3 * needed_for(64) + needed_for(256);
+        let tail = 4096 - used;
+        let stats = alloc.stats();
+        assert_eq!(stats.free_blocks, 3);
+        assert_eq!(stats.free_bytes, needed_for(64) + needed_for(256) + tail);
+        assert_eq!(stats.largest_free_block, tail.max(needed_for(256)));
+    }
 }
@@ -12,15 +12,19 @@ pub(crate) static MEMORY_MANAGER: MemoryManager = MemoryManager::new();
 
 #[cfg_attr(not(test), alloc_error_handler)]
 pub fn alloc_error_handler(layout: Layout) -> ! {
-    panic!("allocation error: {:?}", layout)
+    panic!("allocation error: {:?}, {} bytes in use", layout, MEMORY_MANAGER.used())
 }
 
+fn default_oom_handler(_: Layout, _: usize) {}
+
 pub struct MemoryManager {
     allocator: KernelCell<Option<FreeListAllocator>>,
     used: AtomicUsize,
+    peak: AtomicUsize,
     is_setup: AtomicBool,
     cpu: KernelCell<Option<&'static dyn Cpu>>,
     memory_blocks: KernelCell<Option<MemoryBlocks>>,
+    oom_handler: KernelCell<fn(Layout, usize)>,
 }
 
 impl MemoryManager {
@@ -28,9 +32,11 @@ impl MemoryManager {
         MemoryManager {
             allocator: KernelCell::new(None),
             used: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
             is_setup: AtomicBool::new(false),
             cpu: KernelCell::new(None),
             memory_blocks: KernelCell::new(None),
+            oom_handler: KernelCell::new(default_oom_handler),
         }
     }
 
@@ -44,10 +50,29 @@ impl MemoryManager {
         self.is_setup.store(true, Ordering::SeqCst);
     }
 
+    pub fn set_oom_handler(&self, handler: fn(Layout, usize)) {
+        *self.oom_handler.borrow_mut() = handler;
+    }
+
+    pub unsafe fn alloc_for_task(&self, layout: Layout, task_id: usize) -> *mut u8 {
+        unsafe { self.allocate_as(layout, BlockOwner::Task(task_id)) }
+    }
+
+    pub fn release_task_memory(&self, task_id: usize) {
+        if let Some(allocator) = self.allocator.borrow_mut().as_mut() {
+            let released = unsafe { allocator.deallocate_by_owner(task_id) };
+            self.used.fetch_sub(released, Ordering::Relaxed);
+        }
+    }
+
     pub fn used(&self) -> usize {
         self.used.load(Ordering::Relaxed)
     }
 
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
     pub fn print_config(&self) {
         let memory_blocks = self.memory_blocks.borrow();
         let memory_blocks = memory_blocks.as_ref().expect("MemoryManager not bootstrapped");
@@ -65,31 +90,41 @@ impl MemoryManager {
         }
         crate::kprintln!("[MEMORY] Total: {} MB", total_size / (1024 * 1024));
     }
-}
 
-unsafe impl GlobalAlloc for MemoryManager {
-    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    unsafe fn allocate_as(&self, layout: Layout, owner: BlockOwner) -> *mut u8 {
         let interrupts_enabled = self.is_setup.load(Ordering::Relaxed)
             && self.cpu.borrow().unwrap().are_interrupts_enabled();
         if interrupts_enabled {
             self.cpu.borrow().unwrap().disable_interrupts();
         }
-        self.used.fetch_add(layout.size(), Ordering::Relaxed);
         let result = unsafe {
             self.allocator
                 .borrow_mut()
                 .as_mut()
                 .expect("MemoryManager not bootstrapped")
-                .allocate(layout, BlockOwner::Kernel)
+                .allocate(layout, owner)
         };
         if interrupts_enabled {
             self.cpu.borrow().unwrap().enable_interrupts();
         }
         match result {
-            Ok(ptr) => ptr,
-            Err(_) => ptr::null_mut(),
+            Ok(ptr) => {
+                let used = self.used.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+                self.peak.fetch_max(used, Ordering::Relaxed);
+                ptr
+            }
+            Err(_) => {
+                (self.oom_handler.borrow())(layout, self.used());
+                ptr::null_mut()
+            }
         }
     }
+}
+
+unsafe impl GlobalAlloc for MemoryManager {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { self.allocate_as(layout, BlockOwner::Kernel) }
+    }
 
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         let interrupts_enabled = self.is_setup.load(Ordering::Relaxed)
@@ -109,6 +144,40 @@ unsafe impl GlobalAlloc for MemoryManager {
             self.cpu.borrow().unwrap().enable_interrupts();
         }
     }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let Ok(new_layout) = Layout::from_size_align(new_size, layout.align()) else { return ptr::null_mut() };
+        let interrupts_enabled = self.is_setup.load(Ordering::Relaxed)
+            && self.cpu.borrow().unwrap().are_interrupts_enabled();
+        if interrupts_enabled {
+            self.cpu.borrow().unwrap().disable_interrupts();
+        }
+        let result = unsafe {
+            self.allocator
+                .borrow_mut()
+                .as_mut()
+                .expect("MemoryManager not bootstrapped")
+                .reallocate(ptr, layout, new_layout)
+        };
+        if interrupts_enabled {
+            self.cpu.borrow().unwrap().enable_interrupts();
+        }
+        match result {
+            Ok(new_ptr) => {
+                if new_size >= layout.size() {
+                    let used = self.used.fetch_add(new_size - layout.size(), Ordering::Relaxed) + new_size - layout.size();
+                    self.peak.fetch_max(used, Ordering::Relaxed);
+                } else {
+                    self.used.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+                }
+                new_ptr
+            }
+            Err(_) => {
+                (self.oom_handler.borrow())(new_layout, self.used());
+                ptr::null_mut()
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -117,6 +186,7 @@ mod tests {
     use crate::cpu::Cpu;
     use crate::memory::{MemoryBlock, MAX_MEMORY_BLOCKS};
     use core::alloc::{GlobalAlloc, Layout};
+    use core::sync::atomic::AtomicUsize;
 
     struct MockCpu;
 
@@ -133,6 +203,8 @@ mod tests {
 
     static MOCK_CPU: MockCpu = MockCpu;
 
+    fn ignore_oom(_: Layout, _: usize) {}
+
     fn make_manager(memory: &mut Vec<u8>) -> MemoryManager {
         let manager = MemoryManager::new();
         let blocks = MemoryBlocks {
@@ -186,6 +258,98 @@ mod tests {
         assert_eq!(manager.used(), 0);
     }
 
+    #[test]
+    fn peak_keeps_high_water_mark_after_dealloc() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(256, 8).unwrap();
+
+        let first = unsafe { manager.alloc(small) };
+        let second = unsafe { manager.alloc(large) };
+        unsafe { manager.dealloc(second, large) };
+        unsafe { manager.dealloc(first, small) };
+
+        assert_eq!(manager.used(), 0);
+        assert_eq!(manager.peak(), 320);
+    }
+
+    #[test]
+    fn peak_grows_only_past_previous_high_water_mark() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        let large = Layout::from_size_align(256, 8).unwrap();
+        let small = Layout::from_size_align(64, 8).unwrap();
+
+        let ptr = unsafe { manager.alloc(large) };
+        unsafe { manager.dealloc(ptr, large) };
+        let ptr = unsafe { manager.alloc(small) };
+        unsafe { manager.dealloc(ptr, small) };
+
+        assert_eq!(manager.peak(), 256);
+    }
+
+    #[test]
+    fn failed_alloc_does_not_count_as_used() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        manager.set_oom_handler(ignore_oom);
+        let layout = Layout::from_size_align(4 * 1024 * 1024, 8).unwrap();
+
+        let ptr = unsafe { manager.alloc(layout) };
+
+        assert!(ptr.is_null());
+        assert_eq!(manager.used(), 0);
+        assert_eq!(manager.peak(), 0);
+    }
+
+    static OOM_SIZE: AtomicUsize = AtomicUsize::new(0);
+    static OOM_ALIGN: AtomicUsize = AtomicUsize::new(0);
+    static OOM_USED: AtomicUsize = AtomicUsize::new(0);
+
+    fn record_oom(layout: Layout, used: usize) {
+        OOM_SIZE.store(layout.size(), Ordering::SeqCst);
+        OOM_ALIGN.store(layout.align(), Ordering::SeqCst);
+        OOM_USED.store(used, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn failed_alloc_invokes_oom_handler_with_layout() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        manager.set_oom_handler(record_oom);
+        let held = Layout::from_size_align(64, 8).unwrap();
+        let held_ptr = unsafe { manager.alloc(held) };
+        let layout = Layout::from_size_align(4 * 1024 * 1024, 16).unwrap();
+
+        let ptr = unsafe { manager.alloc(layout) };
+
+        assert!(ptr.is_null());
+        assert_eq!(OOM_SIZE.load(Ordering::SeqCst), 4 * 1024 * 1024);
+        assert_eq!(OOM_ALIGN.load(Ordering::SeqCst), 16);
+        assert_eq!(OOM_USED.load(Ordering::SeqCst), 64);
+        unsafe { manager.dealloc(held_ptr, held) };
+    }
+
+    static OOM_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn count_oom(_: Layout, _: usize) {
+        OOM_CALLS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn successful_alloc_does_not_invoke_oom_handler() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        manager.set_oom_handler(count_oom);
+        let layout = Layout::from_size_align(64, 8).unwrap();
+
+        let ptr = unsafe { manager.alloc(layout) };
+        unsafe { manager.dealloc(ptr, layout) };
+
+        assert_eq!(OOM_CALLS.load(Ordering::SeqCst), 0);
+    }
+
     #[test]
     fn alloc_with_cpu_setup_returns_non_null() {
         let mut memory = vec![0u8; 1024 * 1024];
@@ -196,4 +360,61 @@ mod tests {
         assert!(!ptr.is_null());
         unsafe { manager.dealloc(ptr, layout) };
     }
+
+    #[test]
+    fn release_task_memory_frees_only_that_tasks_blocks() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let kernel_ptr = unsafe { manager.alloc(layout) };
+        let task_ptr = unsafe { manager.alloc_for_task(layout, 7) };
+        unsafe { manager.alloc_for_task(layout, 8) };
+        assert_eq!(manager.used(), 192);
+
+        manager.release_task_memory(7);
+
+        assert_eq!(manager.used(), 128);
+        assert_eq!(unsafe { manager.alloc(layout) }, task_ptr);
+        assert_ne!(kernel_ptr, task_ptr);
+    }
+
+    #[test]
+    fn realloc_keeps_data_and_tracks_the_new_size() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let ptr = unsafe { manager.alloc(layout) };
+        for i in 0..64 {
+            unsafe { *ptr.add(i) = i as u8 };
+        }
+
+        let grown = unsafe { manager.realloc(ptr, layout, 256) };
+        assert!(!grown.is_null());
+        assert_eq!(manager.used(), 256);
+        assert_eq!(manager.peak(), 256);
+        for i in 0..64 {
+            assert_eq!(unsafe { *grown.add(i) }, i as u8);
+        }
+
+        let shrunk = unsafe { manager.realloc(grown, Layout::from_size_align(256, 8).unwrap(), 32) };
+        assert_eq!(shrunk, grown);
+        assert_eq!(manager.used(), 32);
+        unsafe { manager.dealloc(shrunk, Layout::from_size_align(32, 8).unwrap()) };
+        assert_eq!(manager.used(), 0);
+    }
+
+    #[test]
+    fn failed_realloc_keeps_the_original_block() {
+        let mut memory = vec![0u8; 1024 * 1024];
+        let manager = make_manager(&mut memory);
+        manager.set_oom_handler(ignore_oom);
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let ptr = unsafe { manager.alloc(layout) };
+
+        let grown = unsafe { manager.realloc(ptr, layout, 4 * 1024 * 1024) };
+
+        assert!(grown.is_null());
+        assert_eq!(manager.used(), 64);
+        unsafe { manager.dealloc(ptr, layout) };
+    }
 }
@@ -13,5 +13,6 @@ pub fn handle_panic(info: &PanicInfo) -> ! {
     }
     kprintln!("Message: {}", info.message());
     kprintln!("System halted.");
+    crate::default_output::flush();
     loop {}
 }
@@ -1,17 +1,20 @@
 use crate::kernel_services::services;
 use crate::messages::HardwareInterrupt;
-use crate::scheduler::Scheduler;
+use crate::scheduler::{next_or_idle, Scheduler};
 use crate::task::TaskHandle;
 use crate::task::TaskState::{Blocked, Created, Ready, Running, Terminated};
-use alloc::collections::VecDeque;
+use alloc::collections::{BTreeMap, VecDeque};
+use core::cmp::Reverse;
 use system::future::FutureHandle;
 use crate::future::TaskFuture;
 use crate::kernel::kernel;
+use crate::scheduler::timer::Timer;
 
 pub struct FifoScheduler {
     idle_task: Option<TaskHandle>,
-    user_tasks: VecDeque<TaskHandle>,
+    user_tasks: BTreeMap<Reverse<u8>, VecDeque<TaskHandle>>,
     blocked_tasks: VecDeque<TaskFuture>,
+    sleeping_tasks: Timer<TaskHandle>,
     hw_interrupt_queue: VecDeque<HardwareInterrupt>,
 }
 
@@ -25,8 +28,9 @@ impl FifoScheduler {
     pub fn new() -> Self {
         FifoScheduler {
             idle_task: None,
-            user_tasks: VecDeque::with_capacity(5),
+            user_tasks: BTreeMap::new(),
             blocked_tasks: VecDeque::with_capacity(5),
+            sleeping_tasks: Timer::new(),
             hw_interrupt_queue: VecDeque::with_capacity(5),
         }
     }
@@ -34,6 +38,7 @@ impl FifoScheduler {
     pub(crate) fn run(&mut self) {
         loop {
             self.process_hardware_interrupts();
+            self.wake_sleeping(kernel().get_system_time());
             self.pool_futures();
             self.run_user_process();
         }
@@ -41,7 +46,7 @@ impl FifoScheduler {
 
     pub(crate) fn push_task(&mut self, task_handle: TaskHandle) {
         match services().task_manager.borrow().get_state(task_handle) {
-            Ready => self.user_tasks.push_back(task_handle),
+            Ready => self.enqueue_ready(task_handle),
             _ => (),
         }
     }
@@ -58,6 +63,10 @@ impl FifoScheduler {
         self.blocked_tasks.push_back(task_future);
     }
 
+    pub(crate) fn push_sleeping(&mut self, task_handle: TaskHandle, deadline: u64) {
+        self.sleeping_tasks.add_deadline(deadline, task_handle);
+    }
+
     pub(crate) fn set_idle_task(&mut self, idle_task_handle: TaskHandle) -> Result<(), ()> {
         if self.idle_task.is_none() {
             self.idle_task = Some(idle_task_handle);
@@ -67,29 +76,49 @@ impl FifoScheduler {
         }
     }
 
+    pub(crate) fn task_count(&self) -> usize {
+        let ready: usize = self.user_tasks.values().map(|queue| queue.len()).sum();
+        ready + self.blocked_tasks.len() + self.sleeping_tasks.len()
+    }
+
+    fn enqueue_ready(&mut self, task_handle: TaskHandle) {
+        let priority = services().task_manager.borrow().get_priority(task_handle);
+        self.user_tasks
+            .entry(Reverse(priority))
+            .or_default()
+            .push_back(task_handle);
+    }
+
+    fn pop_next_task(&mut self) -> Option<TaskHandle> {
+        loop {
+            let mut entry = self.user_tasks.first_entry()?;
+            let task_handle = entry.get_mut().pop_front();
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+            match task_handle {
+                Some(handle) if services().task_manager.borrow().get_state(handle) == Terminated => {
+                    self.reap(handle);
+                }
+                _ => return task_handle,
+            }
+        }
+    }
+
     fn process_hardware_interrupts(&mut self) {
         while let Some(hardware_interrupt) = self.hw_interrupt_queue.pop_front() {
             match hardware_interrupt {
                 HardwareInterrupt::Keyboard { scancode } => {
-                    if scancode & 0x80 == 0 {
-                        if let Ok(key) = crate::keyboard::Key::from_scancode_set1(scancode) {
-                            let event = crate::keyboard::KeyboardEvent::from_key(key);
-                            if let Some(c) = event.char {
-                                crate::keyboard::push_key(c);
-                            }
-                        }
-                    }
+                    crate::keyboard::push_scancode(scancode);
                 }
             };
         }
     }
 
     fn run_user_process(&mut self) {
-        let next_task_option = self.user_tasks.pop_front();
-        let next_task_handle = match next_task_option {
-            None => self.idle_task.unwrap(),
-            Some(next_task) => next_task,
-        };
+        let next_task_option = self.pop_next_task();
+        let idle_task = self.idle_task.unwrap();
+        let next_task_handle = next_or_idle(self, next_task_option, idle_task);
 
         services().task_manager
             .borrow_mut()
@@ -107,21 +136,23 @@ impl FifoScheduler {
                     .borrow_mut()
                     .set_state(returned_task_handle, Ready);
                 if returned_task_handle != self.idle_task.unwrap() {
-                    self.user_tasks.push_back(returned_task_handle);
+                    self.enqueue_ready(returned_task_handle);
                 } else {
                     self.idle_task = Some(returned_task_handle);
                 }
             }
             Blocked => {}
-            Terminated => {
-                self.cleanup_completion_future(returned_task_handle);
-                services().task_manager
-                    .borrow_mut()
-                    .remove_task(returned_task_handle);
-            }
+            Terminated => self.reap(returned_task_handle),
         }
     }
 
+    fn reap(&mut self, task_handle: TaskHandle) {
+        self.cleanup_completion_future(task_handle);
+        services().task_manager
+            .borrow_mut()
+            .remove_task(task_handle);
+    }
+
     fn cleanup_completion_future(&mut self, task_handle: TaskHandle) {
         let completion_future = services().task_manager.borrow().get_completion_future(task_handle);
         if let Some(future_handle) = completion_future {
@@ -137,14 +168,28 @@ impl FifoScheduler {
         self.cleanup_completion_future(handle);
     }
 
+    pub(crate) fn wake_sleeping(&mut self, now: u64) {
+        for task_handle in self.sleeping_tasks.pop_expired(now).into_iter().flatten() {
+            if services().task_manager.borrow().get_state(task_handle) == Terminated {
+                self.reap(task_handle);
+            } else {
+                services().task_manager.borrow_mut().set_state(task_handle, Ready);
+                self.enqueue_ready(task_handle);
+            }
+        }
+    }
+
     pub(crate) fn pool_futures(&mut self) {
         for _ in 0..self.blocked_tasks.len() {
             if let Some(task_future) = self.blocked_tasks.pop_front() {
-                if task_future.is_completed() {
+                if services().task_manager.borrow().get_state(task_future.task_handle) == Terminated {
+                    services().future_registry.borrow_mut().consume(task_future.future_handle).ok();
+                    self.reap(task_future.task_handle);
+                } else if task_future.is_completed() {
                     services().task_manager
                         .borrow_mut()
                         .set_state(task_future.task_handle, Ready);
-                    self.user_tasks.push_back(task_future.task_handle);
+                    self.enqueue_ready(task_future.task_handle);
                 } else {
                     self.blocked_tasks.push_back(task_future);
                 }
@@ -166,6 +211,10 @@ impl Scheduler for FifoScheduler {
         FifoScheduler::push_blocked(self, task_handle, future_handle);
     }
 
+    fn push_sleeping(&mut self, task_handle: TaskHandle, deadline: u64) {
+        FifoScheduler::push_sleeping(self, task_handle, deadline);
+    }
+
     fn push_hardware_interrupt(&mut self, interrupt: HardwareInterrupt) {
         FifoScheduler::push_hardware_interrupt(self, interrupt);
     }
@@ -177,6 +226,10 @@ impl Scheduler for FifoScheduler {
     fn should_preempt(&mut self) -> bool {
         true
     }
+
+    fn task_count(&self) -> usize {
+        FifoScheduler::task_count(self)
+    }
 }
 
 #[cfg(test)]
@@ -195,7 +248,11 @@ mod tests {
     }
 
     fn create_ready_task(name: &'static str) -> TaskHandle {
-        let task = Task::new(name, 0x1000, 0);
+        create_ready_task_with_priority(name, crate::task::DEFAULT_PRIORITY)
+    }
+
+    fn create_ready_task_with_priority(name: &'static str, priority: u8) -> TaskHandle {
+        let task = Task::with_priority(name, 0x1000, 0, priority);
         let handle = services().task_manager.borrow_mut().add_task(task).unwrap();
         services().task_manager.borrow_mut().set_state(handle, TaskState::Ready);
         handle
@@ -238,4 +295,157 @@ mod tests {
 
         assert!(services().future_registry.borrow_mut().get(future_handle).is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn pop_next_task_returns_none_when_empty() {
+        let mut scheduler = FifoScheduler::new();
+        assert!(scheduler.pop_next_task().is_none());
+    }
+
+    #[test]
+    fn pop_next_task_keeps_fifo_order_within_same_priority() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let first = create_ready_task("First");
+        let second = create_ready_task("Second");
+
+        scheduler.push_task(first);
+        scheduler.push_task(second);
+
+        assert_eq!(scheduler.pop_next_task(), Some(first));
+        assert_eq!(scheduler.pop_next_task(), Some(second));
+        assert_eq!(scheduler.pop_next_task(), None);
+    }
+
+    #[test]
+    fn pop_next_task_dispatches_higher_priority_first() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let low = create_ready_task_with_priority("Low", 10);
+        let normal_a = create_ready_task("NormalA");
+        let high = create_ready_task_with_priority("High", 250);
+        let normal_b = create_ready_task("NormalB");
+
+        scheduler.push_task(low);
+        scheduler.push_task(normal_a);
+        scheduler.push_task(high);
+        scheduler.push_task(normal_b);
+
+        assert_eq!(scheduler.pop_next_task(), Some(high));
+        assert_eq!(scheduler.pop_next_task(), Some(normal_a));
+        assert_eq!(scheduler.pop_next_task(), Some(normal_b));
+        assert_eq!(scheduler.pop_next_task(), Some(low));
+    }
+
+    #[test]
+    fn pop_next_task_skips_and_removes_terminated_tasks() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let killed = create_ready_task("Killed");
+        let alive = create_ready_task("Alive");
+        scheduler.push_task(killed);
+        scheduler.push_task(alive);
+
+        services().task_manager.borrow_mut().set_state(killed, TaskState::Terminated);
+
+        assert_eq!(scheduler.pop_next_task(), Some(alive));
+        assert!(services().task_manager.borrow().get_name(killed).is_none());
+    }
+
+    #[test]
+    fn pool_futures_drops_terminated_blocked_tasks() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let waited_on = create_ready_task("WaitedOn");
+        let future = Box::new(TaskCompletionFuture::new(waited_on));
+        let future_handle = services().future_registry.borrow_mut().register(future).unwrap();
+        let blocked = create_ready_task("Blocked");
+        scheduler.push_blocked(blocked, future_handle);
+
+        services().task_manager.borrow_mut().set_state(blocked, TaskState::Terminated);
+        scheduler.pool_futures();
+
+        assert_eq!(scheduler.task_count(), 0);
+        assert!(services().future_registry.borrow_mut().get(future_handle).is_none());
+    }
+
+    #[test]
+    fn task_count_is_zero_when_empty() {
+        let scheduler = FifoScheduler::new();
+        assert_eq!(scheduler.task_count(), 0);
+    }
+
+    #[test]
+    fn task_count_includes_ready_tasks_across_priorities() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        scheduler.push_task(create_ready_task("A"));
+        scheduler.push_task(create_ready_task_with_priority("B", 10));
+
+        assert_eq!(scheduler.task_count(), 2);
+    }
+
+    #[test]
+    fn task_count_includes_blocked_tasks() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let waited_on = create_ready_task("WaitedOn");
+        let future = Box::new(TaskCompletionFuture::new(waited_on));
+        let future_handle = services().future_registry.borrow_mut().register(future).unwrap();
+
+        scheduler.push_task(create_ready_task("Ready"));
+        scheduler.push_blocked(create_ready_task("Blocked"), future_handle);
+
+        assert_eq!(scheduler.task_count(), 2);
+    }
+
+    fn create_sleeping_task(scheduler: &mut FifoScheduler, name: &'static str, deadline: u64) -> TaskHandle {
+        let handle = create_ready_task(name);
+        services().task_manager.borrow_mut().set_state(handle, TaskState::Blocked);
+        scheduler.push_sleeping(handle, deadline);
+        handle
+    }
+
+    #[test]
+    fn sleeping_tasks_wake_in_deadline_order() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let late = create_sleeping_task(&mut scheduler, "Late", 30);
+        let early = create_sleeping_task(&mut scheduler, "Early", 10);
+        let middle = create_sleeping_task(&mut scheduler, "Middle", 20);
+
+        scheduler.wake_sleeping(30);
+
+        assert_eq!(scheduler.pop_next_task(), Some(early));
+        assert_eq!(scheduler.pop_next_task(), Some(middle));
+        assert_eq!(scheduler.pop_next_task(), Some(late));
+    }
+
+    #[test]
+    fn sleeping_tasks_stay_blocked_until_deadline() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let early = create_sleeping_task(&mut scheduler, "Early", 10);
+        let late = create_sleeping_task(&mut scheduler, "Late", 20);
+
+        scheduler.wake_sleeping(15);
+
+        assert_eq!(scheduler.pop_next_task(), Some(early));
+        assert_eq!(scheduler.pop_next_task(), None);
+        assert_eq!(services().task_manager.borrow().get_state(late), TaskState::Blocked);
+        assert_eq!(scheduler.task_count(), 1);
+    }
+
+    #[test]
+    fn terminated_sleeping_task_is_reaped_on_wake() {
+        setup();
+        let mut scheduler = FifoScheduler::new();
+        let killed = create_sleeping_task(&mut scheduler, "Killed", 10);
+        services().task_manager.borrow_mut().set_state(killed, TaskState::Terminated);
+
+        scheduler.wake_sleeping(10);
+
+        assert_eq!(scheduler.pop_next_task(), None);
+        assert_eq!(scheduler.task_count(), 0);
+    }
+}
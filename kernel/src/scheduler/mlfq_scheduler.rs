@@ -1,4 +1,5 @@
 use alloc::collections::VecDeque;
+use alloc::vec::Vec;
 use crate::kernel_services::services;
 use crate::messages::HardwareInterrupt;
 use crate::task::TaskHandle;
@@ -8,40 +9,67 @@ use system::future::FutureHandle;
 use crate::future::TaskFuture;
 use crate::kernel::kernel;
 use crate::scheduler::Scheduler;
-
-const NUM_QUEUES: usize = 3;
-const QUANTA: [usize; NUM_QUEUES] = [2, 5, 10];
+use crate::scheduler::timer::Timer;
+
+const DEFAULT_LEVELS: usize = 3;
+const QUANTA: [usize; DEFAULT_LEVELS] = [2, 5, 10];
+const BOOST_INTERVAL: usize = 100;
+const AGING_THRESHOLD: u64 = 16;
+
+#[derive(Copy, Clone)]
+struct QueuedTask {
+    handle: TaskHandle,
+    consumed_ticks: usize,
+    enqueued_at: u64,
+}
 
 pub struct MlfqScheduler {
-    queues: [VecDeque<TaskHandle>; NUM_QUEUES],
+    queues: Vec<VecDeque<QueuedTask>>,
+    quanta: Vec<usize>,
     blocked_tasks: VecDeque<TaskFuture>,
+    sleeping_tasks: Timer<TaskHandle>,
     hw_interrupt_queue: VecDeque<HardwareInterrupt>,
     idle_task: Option<TaskHandle>,
-    remaining_quantum: usize
+    remaining_quantum: usize,
+    consumed_ticks: usize,
+    boost_interval: usize,
+    dispatches: u64,
 }
 
 impl MlfqScheduler {
     pub fn new() -> Self {
+        Self::with_levels(DEFAULT_LEVELS)
+    }
+
+    pub fn with_levels(levels: usize) -> Self {
+        assert!(levels > 0, "MLFQ needs at least one level");
         MlfqScheduler {
-            queues: [VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            queues: (0..levels).map(|_| VecDeque::new()).collect(),
+            quanta: (0..levels).map(Self::quantum_for_level).collect(),
             blocked_tasks: VecDeque::new(),
+            sleeping_tasks: Timer::new(),
             hw_interrupt_queue: VecDeque::new(),
             idle_task: None,
-            remaining_quantum: 0usize
+            remaining_quantum: 0usize,
+            consumed_ticks: 0usize,
+            boost_interval: 0usize,
+            dispatches: 0,
         }
     }
 
     pub(crate) fn run(&mut self) {
         loop {
             self.process_hardware_interrupts();
+            self.wake_sleeping(kernel().get_system_time());
             self.poll_futures();
+            self.maybe_boost();
             self.run_next_task();
         }
     }
 
     pub(crate) fn push_task(&mut self, handle: TaskHandle) {
         match services().task_manager.borrow().get_state(handle) {
-            Ready => self.queues[0].push_back(handle),
+            Ready => self.enqueue(0, handle, 0),
             _ => (),
         }
     }
@@ -50,6 +78,24 @@ impl MlfqScheduler {
         self.blocked_tasks.push_back(TaskFuture { task_handle, future_handle });
     }
 
+    pub fn promote(&mut self, handle: TaskHandle) {
+        let mut found = false;
+        for queue in self.queues.iter_mut() {
+            queue.retain(|queued| {
+                let matches = queued.handle == handle;
+                found |= matches;
+                !matches
+            });
+        }
+        if found {
+            self.enqueue(0, handle, 0);
+        }
+    }
+
+    pub(crate) fn push_sleeping(&mut self, task_handle: TaskHandle, deadline: u64) {
+        self.sleeping_tasks.add_deadline(deadline, task_handle);
+    }
+
     pub(crate) fn push_hardware_interrupt(&mut self, interrupt: HardwareInterrupt) {
         self.hw_interrupt_queue.push_back(interrupt);
     }
@@ -63,41 +109,113 @@ impl MlfqScheduler {
         }
     }
 
-    fn next_priority(current: usize, yield_reason: Option<YieldReason>) -> usize {
+    fn quantum_for_level(level: usize) -> usize {
+        let last = QUANTA.len() - 1;
+        QUANTA.get(level).copied().unwrap_or_else(|| {
+            u32::try_from(level - last).ok()
+                .and_then(|shift| 1usize.checked_shl(shift))
+                .and_then(|factor| QUANTA[last].checked_mul(factor))
+                .unwrap_or(usize::MAX)
+        })
+    }
+
+    pub fn levels(&self) -> usize {
+        self.queues.len()
+    }
+
+    fn next_priority(&self, current: usize, yield_reason: Option<YieldReason>) -> usize {
         match yield_reason {
             None => 0,
             Some(YieldReason::Voluntary) => current,
-            Some(YieldReason::Preempted) => (current + 1).min(NUM_QUEUES - 1),
+            Some(YieldReason::Preempted) => (current + 1).min(self.levels() - 1),
+        }
+    }
+
+    fn enqueue(&mut self, priority: usize, handle: TaskHandle, consumed_ticks: usize) {
+        self.queues[priority].push_back(QueuedTask { handle, consumed_ticks, enqueued_at: self.dispatches });
+    }
+
+    fn age_waiting_tasks(&mut self) {
+        for level in 1..self.levels() {
+            while let Some(&queued) = self.queues[level].front() {
+                if self.dispatches - queued.enqueued_at < AGING_THRESHOLD {
+                    break;
+                }
+                self.queues[level].pop_front();
+                self.enqueue(level - 1, queued.handle, 0);
+            }
         }
     }
 
     fn take_next_handle(&mut self) -> Option<(TaskHandle, usize)> {
-        for (priority, queue) in self.queues.iter_mut().enumerate() {
-            if let Some(handle) = queue.pop_front() {
-                return Some((handle, priority));
+        self.age_waiting_tasks();
+        for priority in 0..self.levels() {
+            while let Some(queued) = self.queues[priority].pop_front() {
+                if services().task_manager.borrow().get_state(queued.handle) == Terminated {
+                    self.reap(queued.handle);
+                    continue;
+                }
+                self.consumed_ticks = queued.consumed_ticks;
+                self.dispatches += 1;
+                return Some((queued.handle, priority));
             }
         }
         None
     }
 
     fn requeue_after_run(&mut self, handle: TaskHandle, priority: usize) {
-        let yield_reason = services().task_manager.borrow().get_yield_reason(handle);
-        let new_priority = Self::next_priority(priority, yield_reason);
-        self.queues[new_priority].push_back(handle);
+        let yield_reason = if self.consumed_ticks >= self.quanta[priority] {
+            Some(YieldReason::Preempted)
+        } else {
+            services().task_manager.borrow().get_yield_reason(handle)
+        };
+        let new_priority = self.next_priority(priority, yield_reason);
+        let carried_ticks = if new_priority == priority { self.consumed_ticks } else { 0 };
+        self.enqueue(new_priority, handle, carried_ticks);
+    }
+
+    pub(crate) fn task_count(&self) -> usize {
+        let ready: usize = self.queues.iter().map(|queue| queue.len()).sum();
+        ready + self.blocked_tasks.len() + self.sleeping_tasks.len()
+    }
+
+    pub(crate) fn record_tick(&mut self) {
+        self.consumed_ticks += 1;
     }
 
     fn reset_quantum(&mut self, priority: usize) {
-        self.remaining_quantum = QUANTA[priority];
+        self.remaining_quantum = self.quanta[priority];
+    }
+
+    fn start_slice(&mut self, priority: usize) {
+        self.reset_quantum(priority);
+        self.remaining_quantum = self.remaining_quantum.saturating_sub(self.consumed_ticks);
+    }
+
+    fn maybe_boost(&mut self) {
+        self.boost_interval += 1;
+        if self.boost_interval < BOOST_INTERVAL {
+            return;
+        }
+        self.boost_interval = 0;
+        let (top, lower) = self.queues.split_at_mut(1);
+        for queue in lower {
+            top[0].extend(queue.drain(..).map(|queued| QueuedTask { consumed_ticks: 0, ..queued }));
+        }
     }
 
     fn run_next_task(&mut self) {
         let (next_handle, priority) = match self.take_next_handle() {
             Some((handle, priority)) => (handle, priority),
-            None => (self.idle_task.unwrap(), 0),
+            None => {
+                self.consumed_ticks = 0;
+                self.on_idle();
+                (self.idle_task.unwrap(), 0)
+            }
         };
 
         services().task_manager.borrow_mut().set_state(next_handle, Running);
-        self.reset_quantum(priority);
+        self.start_slice(priority);
         let returned_handle = kernel().switch_to_task(next_handle);
 
         let task_state = services().task_manager.borrow().get_state(returned_handle);
@@ -110,25 +228,20 @@ impl MlfqScheduler {
                 }
             }
             Blocked => {}
-            Terminated => {
-                self.cleanup_completion_future(returned_handle);
-                services().task_manager.borrow_mut().remove_task(returned_handle);
-            }
+            Terminated => self.reap(returned_handle),
         }
     }
 
+    fn reap(&mut self, handle: TaskHandle) {
+        self.cleanup_completion_future(handle);
+        services().task_manager.borrow_mut().remove_task(handle);
+    }
+
     fn process_hardware_interrupts(&mut self) {
         while let Some(interrupt) = self.hw_interrupt_queue.pop_front() {
             match interrupt {
                 HardwareInterrupt::Keyboard { scancode } => {
-                    if scancode & 0x80 == 0 {
-                        if let Ok(key) = crate::keyboard::Key::from_scancode_set1(scancode) {
-                            let event = crate::keyboard::KeyboardEvent::from_key(key);
-                            if let Some(c) = event.char {
-                                crate::keyboard::push_key(c);
-                            }
-                        }
-                    }
+                    crate::keyboard::push_scancode(scancode);
                 }
             }
         }
@@ -144,15 +257,29 @@ impl MlfqScheduler {
         }
     }
 
+    fn wake_sleeping(&mut self, now: u64) {
+        for task_handle in self.sleeping_tasks.pop_expired(now).into_iter().flatten() {
+            if services().task_manager.borrow().get_state(task_handle) == Terminated {
+                self.reap(task_handle);
+            } else {
+                services().task_manager.borrow_mut().set_state(task_handle, Ready);
+                self.enqueue(0, task_handle, 0);
+            }
+        }
+    }
+
     fn poll_futures(&mut self) {
         for _ in 0..self.blocked_tasks.len() {
             if let Some(task_future) = self.blocked_tasks.pop_front() {
-                if task_future.is_completed() {
+                if services().task_manager.borrow().get_state(task_future.task_handle) == Terminated {
+                    services().future_registry.borrow_mut().consume(task_future.future_handle).ok();
+                    self.reap(task_future.task_handle);
+                } else if task_future.is_completed() {
                     services()
                         .task_manager
                         .borrow_mut()
                         .set_state(task_future.task_handle, Ready);
-                    self.queues[0].push_back(task_future.task_handle);
+                    self.enqueue(0, task_future.task_handle, 0);
                 } else {
                     self.blocked_tasks.push_back(task_future);
                 }
@@ -165,6 +292,11 @@ impl MlfqScheduler {
         self.queues[priority].len()
     }
 
+    #[cfg(test)]
+    pub(crate) fn level_of(&self, handle: TaskHandle) -> Option<usize> {
+        self.queues.iter().position(|queue| queue.iter().any(|queued| queued.handle == handle))
+    }
+
     #[cfg(test)]
     pub(crate) fn poll_futures_for_test(&mut self) {
         self.poll_futures();
@@ -189,6 +321,10 @@ impl Scheduler for MlfqScheduler {
         MlfqScheduler::push_blocked(self, task_handle, future_handle);
     }
 
+    fn push_sleeping(&mut self, task_handle: TaskHandle, deadline: u64) {
+        MlfqScheduler::push_sleeping(self, task_handle, deadline);
+    }
+
     fn push_hardware_interrupt(&mut self, interrupt: HardwareInterrupt) {
         MlfqScheduler::push_hardware_interrupt(self, interrupt);
     }
@@ -198,9 +334,14 @@ impl Scheduler for MlfqScheduler {
     }
 
     fn should_preempt(&mut self) -> bool {
+        self.record_tick();
         self.remaining_quantum = self.remaining_quantum.saturating_sub(1);
         self.remaining_quantum == 0
     }
+
+    fn task_count(&self) -> usize {
+        MlfqScheduler::task_count(self)
+    }
 }
 
 #[cfg(test)]
@@ -227,26 +368,87 @@ mod tests {
 
     #[test]
     fn next_priority_with_no_yield_reason_returns_queue_0() {
-        assert_eq!(MlfqScheduler::next_priority(1, None), 0);
-        assert_eq!(MlfqScheduler::next_priority(2, None), 0);
+        let scheduler = MlfqScheduler::new();
+        assert_eq!(scheduler.next_priority(1, None), 0);
+        assert_eq!(scheduler.next_priority(2, None), 0);
     }
 
     #[test]
     fn next_priority_with_voluntary_keeps_current_priority() {
-        assert_eq!(MlfqScheduler::next_priority(0, Some(YieldReason::Voluntary)), 0);
-        assert_eq!(MlfqScheduler::next_priority(1, Some(YieldReason::Voluntary)), 1);
-        assert_eq!(MlfqScheduler::next_priority(2, Some(YieldReason::Voluntary)), 2);
+        let scheduler = MlfqScheduler::new();
+        assert_eq!(scheduler.next_priority(0, Some(YieldReason::Voluntary)), 0);
+        assert_eq!(scheduler.next_priority(1, Some(YieldReason::Voluntary)), 1);
+        assert_eq!(scheduler.next_priority(2, Some(YieldReason::Voluntary)), 2);
     }
 
     #[test]
     fn next_priority_with_preempted_demotes_one_level() {
-        assert_eq!(MlfqScheduler::next_priority(0, Some(YieldReason::Preempted)), 1);
-        assert_eq!(MlfqScheduler::next_priority(1, Some(YieldReason::Preempted)), 2);
+        let scheduler = MlfqScheduler::new();
+        assert_eq!(scheduler.next_priority(0, Some(YieldReason::Preempted)), 1);
+        assert_eq!(scheduler.next_priority(1, Some(YieldReason::Preempted)), 2);
     }
 
     #[test]
     fn next_priority_with_preempted_at_lowest_stays_at_lowest() {
-        assert_eq!(MlfqScheduler::next_priority(2, Some(YieldReason::Preempted)), 2);
+        let scheduler = MlfqScheduler::new();
+        assert_eq!(scheduler.next_priority(2, Some(YieldReason::Preempted)), 2);
+    }
+
+    #[test]
+    fn new_scheduler_has_default_number_of_levels() {
+        assert_eq!(MlfqScheduler::new().levels(), DEFAULT_LEVELS);
+    }
+
+    #[test]
+    fn demotion_saturates_at_last_level_for_two_levels() {
+        let scheduler = MlfqScheduler::with_levels(2);
+        assert_eq!(scheduler.levels(), 2);
+        assert_eq!(scheduler.next_priority(0, Some(YieldReason::Preempted)), 1);
+        assert_eq!(scheduler.next_priority(1, Some(YieldReason::Preempted)), 1);
+    }
+
+    #[test]
+    fn demotion_saturates_at_last_level_for_five_levels() {
+        let scheduler = MlfqScheduler::with_levels(5);
+        let mut priority = 0;
+        for _ in 0..10 {
+            priority = scheduler.next_priority(priority, Some(YieldReason::Preempted));
+        }
+        assert_eq!(priority, 4);
+    }
+
+    #[test]
+    fn extra_levels_double_the_last_default_quantum() {
+        let mut scheduler = MlfqScheduler::with_levels(5);
+        scheduler.reset_quantum(3);
+        assert_eq!(scheduler.remaining_quantum, 2 * QUANTA[DEFAULT_LEVELS - 1]);
+        scheduler.reset_quantum(4);
+        assert_eq!(scheduler.remaining_quantum, 4 * QUANTA[DEFAULT_LEVELS - 1]);
+    }
+
+    #[test]
+    fn deep_levels_saturate_the_quantum_instead_of_overflowing() {
+        let last = DEFAULT_LEVELS - 1;
+        assert_eq!(MlfqScheduler::quantum_for_level(last + usize::BITS as usize - 3), usize::MAX);
+        assert_eq!(MlfqScheduler::quantum_for_level(last + usize::BITS as usize), usize::MAX);
+        assert_eq!(MlfqScheduler::quantum_for_level(usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn preempted_task_sinks_to_last_level_with_two_levels() {
+        setup();
+        let mut scheduler = MlfqScheduler::with_levels(2);
+        let h = create_ready_task("T");
+        scheduler.push_task(h);
+
+        for _ in 0..3 {
+            let (taken, p) = scheduler.take_next_handle().unwrap();
+            services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Preempted);
+            scheduler.requeue_after_run(taken, p);
+        }
+
+        assert_eq!(scheduler.queue_len(0), 0);
+        assert_eq!(scheduler.queue_len(1), 1);
     }
 
     #[test]
@@ -337,6 +539,60 @@ mod tests {
         assert_eq!(scheduler.queue_len(1), 0);
     }
 
+    fn demote_to_lowest_level(scheduler: &mut MlfqScheduler) {
+        for _ in 0..scheduler.levels() - 1 {
+            let (taken, p) = scheduler.take_next_handle().unwrap();
+            services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Preempted);
+            scheduler.requeue_after_run(taken, p);
+        }
+    }
+
+    #[test]
+    fn promote_moves_task_from_lowest_queue_to_queue_0() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+        scheduler.push_task(h);
+        demote_to_lowest_level(&mut scheduler);
+        assert_eq!(scheduler.queue_len(2), 1);
+
+        scheduler.promote(h);
+
+        assert_eq!(scheduler.queue_len(2), 0);
+        assert_eq!(scheduler.queue_len(0), 1);
+        let (taken, priority) = scheduler.take_next_handle().unwrap();
+        assert_eq!(taken, h);
+        assert_eq!(priority, 0);
+        assert_eq!(scheduler.consumed_ticks, 0);
+    }
+
+    #[test]
+    fn promoted_task_runs_after_tasks_already_in_queue_0() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let demoted = create_ready_task("Demoted");
+        scheduler.push_task(demoted);
+        demote_to_lowest_level(&mut scheduler);
+        let waiting = create_ready_task("Waiting");
+        scheduler.push_task(waiting);
+
+        scheduler.promote(demoted);
+
+        assert_eq!(scheduler.take_next_handle().unwrap().0, waiting);
+        assert_eq!(scheduler.take_next_handle().unwrap().0, demoted);
+    }
+
+    #[test]
+    fn promote_ignores_tasks_not_in_any_queue() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+
+        scheduler.promote(h);
+
+        assert_eq!(scheduler.task_count(), 0);
+    }
+
     #[test]
     fn poll_futures_places_unblocked_task_in_queue_0() {
         setup();
@@ -358,6 +614,38 @@ mod tests {
         assert_eq!(scheduler.queue_len(2), 0);
     }
 
+    #[test]
+    fn take_next_handle_skips_and_removes_terminated_tasks() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let killed = create_ready_task("Killed");
+        let alive = create_ready_task("Alive");
+        scheduler.push_task(killed);
+        scheduler.push_task(alive);
+
+        services().task_manager.borrow_mut().set_state(killed, TaskState::Terminated);
+
+        assert_eq!(scheduler.take_next_handle(), Some((alive, 0)));
+        assert!(services().task_manager.borrow().get_name(killed).is_none());
+    }
+
+    #[test]
+    fn poll_futures_drops_terminated_blocked_tasks() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let waited_on = create_ready_task("WaitedOn");
+        let future = Box::new(TaskCompletionFuture::new(waited_on));
+        let future_handle = services().future_registry.borrow_mut().register(future).unwrap();
+        let blocked = create_ready_task("Blocked");
+        scheduler.push_blocked(blocked, future_handle);
+
+        services().task_manager.borrow_mut().set_state(blocked, TaskState::Terminated);
+        scheduler.poll_futures_for_test();
+
+        assert_eq!(scheduler.task_count(), 0);
+        assert!(services().future_registry.borrow_mut().get(future_handle).is_none());
+    }
+
     #[test]
     fn take_next_returns_none_when_all_queues_empty() {
         let mut scheduler = MlfqScheduler::new();
@@ -422,6 +710,43 @@ mod tests {
         assert!(scheduler.should_preempt());
     }
 
+    #[test]
+    fn should_preempt_counts_ticks_carried_over_from_previous_slice() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let handle = create_ready_task("Carried");
+        scheduler.enqueue(1, handle, 3);
+
+        let (_, priority) = scheduler.take_next_handle().unwrap();
+        scheduler.start_slice(priority);
+
+        for _ in 0..QUANTA[1] - 3 - 1 {
+            assert!(!scheduler.should_preempt());
+        }
+        assert!(scheduler.should_preempt());
+    }
+
+    #[test]
+    fn should_preempt_resets_for_fresh_task_after_switch() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        scheduler.enqueue(0, create_ready_task("First"), 0);
+        scheduler.enqueue(0, create_ready_task("Second"), 0);
+
+        let (_, priority) = scheduler.take_next_handle().unwrap();
+        scheduler.start_slice(priority);
+        for _ in 0..QUANTA[0] {
+            scheduler.should_preempt();
+        }
+        assert_eq!(scheduler.consumed_ticks, QUANTA[0]);
+
+        let (_, priority) = scheduler.take_next_handle().unwrap();
+        scheduler.start_slice(priority);
+
+        assert_eq!(scheduler.consumed_ticks, 0);
+        assert!(!scheduler.should_preempt());
+    }
+
     #[test]
     fn orphaned_completion_future_is_removed_when_task_terminates() {
         setup();
@@ -476,4 +801,268 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn record_tick_accumulates_consumed_ticks() {
+        let mut scheduler = MlfqScheduler::new();
+        scheduler.record_tick();
+        scheduler.record_tick();
+        assert_eq!(scheduler.consumed_ticks, 2);
+    }
+
+    #[test]
+    fn take_next_handle_resets_consumed_ticks_for_new_task() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+        scheduler.record_tick();
+        scheduler.push_task(h);
+
+        scheduler.take_next_handle().unwrap();
+
+        assert_eq!(scheduler.consumed_ticks, 0);
+    }
+
+    #[test]
+    fn voluntary_yield_before_quantum_keeps_priority_and_carries_ticks() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+        scheduler.push_task(h);
+
+        let (taken, priority) = scheduler.take_next_handle().unwrap();
+        for _ in 0..QUANTA[0] - 1 {
+            scheduler.record_tick();
+        }
+        services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Voluntary);
+        scheduler.requeue_after_run(taken, priority);
+
+        assert_eq!(scheduler.queue_len(0), 1);
+        scheduler.take_next_handle().unwrap();
+        assert_eq!(scheduler.consumed_ticks, QUANTA[0] - 1);
+    }
+
+    #[test]
+    fn voluntary_yield_after_accumulated_quantum_demotes_task() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+        scheduler.push_task(h);
+
+        let (taken, priority) = scheduler.take_next_handle().unwrap();
+        for _ in 0..QUANTA[0] - 1 {
+            scheduler.record_tick();
+        }
+        services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Voluntary);
+        scheduler.requeue_after_run(taken, priority);
+
+        let (taken, priority) = scheduler.take_next_handle().unwrap();
+        scheduler.record_tick();
+        services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Voluntary);
+        scheduler.requeue_after_run(taken, priority);
+
+        assert_eq!(scheduler.queue_len(0), 0);
+        assert_eq!(scheduler.queue_len(1), 1);
+        scheduler.take_next_handle().unwrap();
+        assert_eq!(scheduler.consumed_ticks, 0);
+    }
+
+    #[test]
+    fn should_preempt_records_a_tick() {
+        let mut scheduler = MlfqScheduler::new();
+        scheduler.remaining_quantum = QUANTA[0];
+        scheduler.should_preempt();
+        assert_eq!(scheduler.consumed_ticks, 1);
+    }
+
+    #[test]
+    fn maybe_boost_does_nothing_before_interval() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+        scheduler.push_task(h);
+        let (taken, p) = scheduler.take_next_handle().unwrap();
+        services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Preempted);
+        scheduler.requeue_after_run(taken, p);
+
+        for _ in 0..BOOST_INTERVAL - 1 {
+            scheduler.maybe_boost();
+        }
+
+        assert_eq!(scheduler.queue_len(0), 0);
+        assert_eq!(scheduler.queue_len(1), 1);
+    }
+
+    #[test]
+    fn maybe_boost_moves_demoted_tasks_back_to_queue_0() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+        scheduler.push_task(h);
+        for _ in 0..2 {
+            let (taken, p) = scheduler.take_next_handle().unwrap();
+            services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Preempted);
+            scheduler.requeue_after_run(taken, p);
+        }
+        assert_eq!(scheduler.queue_len(2), 1);
+
+        for _ in 0..BOOST_INTERVAL {
+            scheduler.maybe_boost();
+        }
+
+        assert_eq!(scheduler.queue_len(0), 1);
+        assert_eq!(scheduler.queue_len(1), 0);
+        assert_eq!(scheduler.queue_len(2), 0);
+        assert_eq!(scheduler.take_next_handle(), Some((h, 0)));
+    }
+
+    #[test]
+    fn maybe_boost_preserves_fifo_order_across_levels() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let top = create_ready_task("Top");
+        let middle = create_ready_task("Middle");
+        let bottom = create_ready_task("Bottom");
+        scheduler.enqueue(0, top, 0);
+        scheduler.enqueue(1, middle, 0);
+        scheduler.enqueue(2, bottom, 0);
+
+        for _ in 0..BOOST_INTERVAL {
+            scheduler.maybe_boost();
+        }
+
+        assert_eq!(scheduler.take_next_handle(), Some((top, 0)));
+        assert_eq!(scheduler.take_next_handle(), Some((middle, 0)));
+        assert_eq!(scheduler.take_next_handle(), Some((bottom, 0)));
+    }
+
+    #[test]
+    fn starved_low_level_task_is_promoted_after_aging_threshold() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let starved = create_ready_task("Starved");
+        scheduler.push_task(starved);
+        let (taken, p) = scheduler.take_next_handle().unwrap();
+        scheduler.start_slice(p);
+        while !Scheduler::should_preempt(&mut scheduler) {}
+        scheduler.requeue_after_run(taken, p);
+        assert_eq!(scheduler.level_of(starved), Some(1));
+        let interactive = create_ready_task("Interactive");
+        scheduler.push_task(interactive);
+
+        for _ in 0..=AGING_THRESHOLD {
+            let (taken, p) = scheduler.take_next_handle().unwrap();
+            assert_eq!((taken, p), (interactive, 0));
+            services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Voluntary);
+            scheduler.requeue_after_run(taken, p);
+        }
+
+        assert_eq!(scheduler.level_of(starved), Some(0));
+        assert_eq!(scheduler.take_next_handle(), Some((starved, 0)));
+    }
+
+    #[test]
+    fn aged_task_climbs_one_level_at_a_time() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let starved = create_ready_task("Starved");
+        scheduler.push_task(starved);
+        for _ in 0..2 {
+            let (taken, p) = scheduler.take_next_handle().unwrap();
+            scheduler.start_slice(p);
+            while !Scheduler::should_preempt(&mut scheduler) {}
+            scheduler.requeue_after_run(taken, p);
+        }
+        assert_eq!(scheduler.level_of(starved), Some(2));
+        let interactive = create_ready_task("Interactive");
+        scheduler.push_task(interactive);
+
+        for _ in 0..=AGING_THRESHOLD {
+            let (taken, p) = scheduler.take_next_handle().unwrap();
+            services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Voluntary);
+            scheduler.requeue_after_run(taken, p);
+        }
+
+        assert_eq!(scheduler.level_of(starved), Some(1));
+    }
+
+    #[test]
+    fn task_count_is_zero_when_empty() {
+        let scheduler = MlfqScheduler::new();
+        assert_eq!(scheduler.task_count(), 0);
+    }
+
+    #[test]
+    fn task_count_sums_all_queues() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        scheduler.enqueue(0, create_ready_task("A"), 0);
+        scheduler.enqueue(1, create_ready_task("B"), 0);
+        scheduler.enqueue(2, create_ready_task("C"), 0);
+
+        assert_eq!(scheduler.task_count(), 3);
+    }
+
+    #[test]
+    fn task_count_includes_blocked_tasks() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let waited_on = create_ready_task("WaitedOn");
+        let future = Box::new(TaskCompletionFuture::new(waited_on));
+        let future_handle = services().future_registry.borrow_mut().register(future).unwrap();
+
+        scheduler.push_task(create_ready_task("Ready"));
+        scheduler.push_blocked(create_ready_task("Blocked"), future_handle);
+
+        assert_eq!(scheduler.task_count(), 2);
+    }
+
+    fn create_sleeping_task(scheduler: &mut MlfqScheduler, name: &'static str, deadline: u64) -> TaskHandle {
+        let handle = create_ready_task(name);
+        services().task_manager.borrow_mut().set_state(handle, TaskState::Blocked);
+        scheduler.push_sleeping(handle, deadline);
+        handle
+    }
+
+    #[test]
+    fn sleeping_tasks_wake_into_queue_0_in_deadline_order() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let late = create_sleeping_task(&mut scheduler, "Late", 30);
+        let early = create_sleeping_task(&mut scheduler, "Early", 10);
+
+        scheduler.wake_sleeping(10);
+        assert_eq!(scheduler.queue_len(0), 1);
+        scheduler.wake_sleeping(30);
+
+        assert_eq!(scheduler.take_next_handle(), Some((early, 0)));
+        assert_eq!(scheduler.take_next_handle(), Some((late, 0)));
+    }
+
+    #[test]
+    fn task_count_includes_sleeping_tasks() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        create_sleeping_task(&mut scheduler, "Sleeping", 10);
+
+        assert_eq!(scheduler.task_count(), 1);
+    }
+
+    #[test]
+    fn voluntary_yield_keeps_a_demoted_task_at_its_level() {
+        setup();
+        let mut scheduler = MlfqScheduler::new();
+        let h = create_ready_task("T");
+        scheduler.push_task(h);
+        let (taken, p) = scheduler.take_next_handle().unwrap();
+        services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Preempted);
+        scheduler.requeue_after_run(taken, p);
+        assert_eq!(scheduler.level_of(h), Some(1));
+
+        let (taken, p) = scheduler.take_next_handle().unwrap();
+        services().task_manager.borrow_mut().set_yield_reason(taken, YieldReason::Voluntary);
+        scheduler.requeue_after_run(taken, p);
+
+        assert_eq!(scheduler.level_of(h), Some(1));
+    }
+}
@@ -13,9 +13,24 @@ pub trait Scheduler {
     fn run(&mut self);
     fn push_task(&mut self, handle: TaskHandle);
     fn push_blocked(&mut self, task_handle: TaskHandle, future_handle: FutureHandle);
+    fn push_sleeping(&mut self, task_handle: TaskHandle, deadline: u64);
     fn push_hardware_interrupt(&mut self, interrupt: HardwareInterrupt);
     fn set_idle_task(&mut self, handle: TaskHandle) -> Result<(), ()>;
     fn should_preempt(&mut self) -> bool;
+    fn on_idle(&mut self) {}
+    /// Ready, blocked and sleeping tasks held by the scheduler, excluding the idle task.
+    fn task_count(&self) -> usize;
+}
+
+pub(crate) fn next_or_idle<S: Scheduler + ?Sized>(
+    scheduler: &mut S,
+    next: Option<TaskHandle>,
+    idle_task: TaskHandle,
+) -> TaskHandle {
+    next.unwrap_or_else(|| {
+        scheduler.on_idle();
+        idle_task
+    })
 }
 
 pub type SchedulerFactory = fn() -> Box<dyn Scheduler>;
@@ -26,4 +41,47 @@ pub fn mfq_scheduler() -> Box<dyn Scheduler> {
 
 pub fn fifo_scheduler() -> Box<dyn Scheduler> {
     Box::new(FifoScheduler::new())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct IdleCountingScheduler {
+        idle_calls: usize,
+    }
+
+    impl Scheduler for IdleCountingScheduler {
+        fn run(&mut self) {}
+        fn push_task(&mut self, _: TaskHandle) {}
+        fn push_blocked(&mut self, _: TaskHandle, _: FutureHandle) {}
+        fn push_sleeping(&mut self, _: TaskHandle, _: u64) {}
+        fn push_hardware_interrupt(&mut self, _: HardwareInterrupt) {}
+        fn set_idle_task(&mut self, _: TaskHandle) -> Result<(), ()> { Ok(()) }
+        fn should_preempt(&mut self) -> bool { false }
+        fn on_idle(&mut self) {
+            self.idle_calls += 1;
+        }
+        fn task_count(&self) -> usize { 0 }
+    }
+
+    #[test]
+    fn next_or_idle_dispatches_runnable_task_without_idling() {
+        let mut scheduler = IdleCountingScheduler::default();
+        let task = TaskHandle::new(1, 0);
+        let idle = TaskHandle::new(2, 0);
+
+        assert_eq!(next_or_idle(&mut scheduler, Some(task), idle), task);
+        assert_eq!(scheduler.idle_calls, 0);
+    }
+
+    #[test]
+    fn next_or_idle_invokes_hook_when_nothing_is_runnable() {
+        let mut scheduler = IdleCountingScheduler::default();
+        let idle = TaskHandle::new(2, 0);
+
+        assert_eq!(next_or_idle(&mut scheduler, None, idle), idle);
+        assert_eq!(scheduler.idle_calls, 1);
+    }
+}
@@ -3,11 +3,11 @@ use alloc::vec::Vec;
 use core::time::Duration;
 use system::future::FutureHandle;
 
-pub(crate) struct Timer {
-    next: BTreeMap<u64, Vec<FutureHandle>>
+pub(crate) struct Timer<T = FutureHandle> {
+    next: BTreeMap<u64, Vec<T>>
 }
 
-impl Timer {
+impl<T> Timer<T> {
 
     pub fn new() -> Self {
         Timer {
@@ -15,15 +15,25 @@ impl Timer {
         }
     }
 
-    pub fn add_sleep(&mut self, now: u64, sleep: Duration, future_handle: FutureHandle) {
-        let deadline = now + (sleep.as_millis() as u64);
-        self.next.entry(deadline).or_insert_with(Vec::new).push(future_handle)
+    pub fn add_sleep(&mut self, now: u64, sleep: Duration, item: T) {
+        self.add_deadline(now + (sleep.as_millis() as u64), item)
     }
 
-    pub fn pop_expired(&mut self, now: u64) -> Option<Vec<FutureHandle>> {
+    pub fn add_deadline(&mut self, deadline: u64, item: T) {
+        self.next.entry(deadline).or_insert_with(Vec::new).push(item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.next.values().map(Vec::len).sum()
+    }
+
+    pub fn pop_expired(&mut self, now: u64) -> Option<Vec<T>> {
+        if self.next.first_key_value().is_none_or(|(&deadline, _)| deadline > now) {
+            return None;
+        }
         let remaining = self.next.split_off(&(now + 1));
         let expired = core::mem::replace(&mut self.next, remaining);
-        let handles: Vec<FutureHandle> = expired.into_values().flatten().collect();
+        let handles: Vec<T> = expired.into_values().flatten().collect();
         if handles.is_empty() { None } else { Some(handles) }
     }
 }
@@ -39,7 +49,7 @@ mod tests {
 
     #[test]
     fn pop_expired_returns_none_when_empty() {
-        let mut timer = Timer::new();
+        let mut timer: Timer = Timer::new();
         assert!(timer.pop_expired(100).is_none());
     }
 
@@ -104,6 +114,29 @@ mod tests {
         assert_eq!(expired, alloc::vec![handle(1), handle(2)]);
     }
 
+    #[test]
+    fn add_deadline_out_of_order_pops_in_deadline_order() {
+        let mut timer = Timer::new();
+        timer.add_deadline(30, handle(3));
+        timer.add_deadline(10, handle(1));
+        timer.add_deadline(20, handle(2));
+
+        assert_eq!(timer.pop_expired(15), Some(alloc::vec![handle(1)]));
+        assert_eq!(timer.pop_expired(30), Some(alloc::vec![handle(2), handle(3)]));
+    }
+
+    #[test]
+    fn len_counts_items_across_deadlines() {
+        let mut timer = Timer::new();
+        timer.add_deadline(10, handle(1));
+        timer.add_deadline(10, handle(2));
+        timer.add_deadline(20, handle(3));
+
+        assert_eq!(timer.len(), 3);
+        timer.pop_expired(10);
+        assert_eq!(timer.len(), 1);
+    }
+
     #[test]
     fn add_sleep_uses_now_as_base_for_deadline() {
         let mut timer = Timer::new();
@@ -1,7 +1,6 @@
 use core::alloc::{GlobalAlloc, Layout};
 use alloc::boxed::Box;
 use alloc::string::String;
-use crate::future::TimeFuture;
 use crate::kernel::kernel;
 use crate::kernel_services::services;
 use crate::default_output::print;
@@ -10,7 +9,7 @@ use collections::generational_arena::HalfSize;
 use system::future::FutureHandle;
 use system::ipc::{IpcReplyFuture, IpcServerHandle};
 use system::ipc::IpcSendMessage;
-use crate::task::{new_elf_task, new_entrypoint_task};
+use crate::task::{new_elf_task, new_entrypoint_task, TaskHandle};
 
 #[cfg(not(test))]
 pub fn handle_syscall(num: usize, arg1: usize, arg2: usize, arg3: usize) -> usize {
@@ -20,13 +19,18 @@ pub fn handle_syscall(num: usize, arg1: usize, arg2: usize, arg3: usize) -> usiz
             print(format_args!("{}", s));
             0
         }
+        Ok(SyscallNum::Write) => {
+            let bytes = unsafe { core::slice::from_raw_parts(arg2 as *const u8, arg3) };
+            crate::default_output::write(arg1, bytes)
+        }
         Ok(SyscallNum::Sleep) => {
-            let future = Box::new(TimeFuture::new(arg1 as u64));
-            let handle = services().future_registry
-                .borrow_mut()
-                .register(future)
-                .expect("Failed to register sleep future");
-            let _ = kernel().wait_future(handle);
+            sleep(arg1 as u64);
+            0
+        }
+        Ok(SyscallNum::SleepUntil) => {
+            if let Some(ms) = remaining_sleep(kernel().get_system_time(), arg1 as u64) {
+                sleep(ms);
+            }
             0
         }
         Ok(SyscallNum::Exec) => {
@@ -36,6 +40,32 @@ pub fn handle_syscall(num: usize, arg1: usize, arg2: usize, arg3: usize) -> usiz
                 None => u64::MAX as usize,
             }
         }
+        Ok(SyscallNum::Spawn) => {
+            let bytes = unsafe { core::slice::from_raw_parts(arg2 as *const u8, arg3) };
+            let Ok(name) = core::str::from_utf8(bytes) else { return u64::MAX as usize };
+            match kernel().spawn(name, arg1) {
+                Some(handle) => handle.pack(),
+                None => u64::MAX as usize,
+            }
+        }
+        Ok(SyscallNum::Kill) => {
+            if kernel().kill(TaskHandle::unpack(arg1)) { 1 } else { 0 }
+        }
+        Ok(SyscallNum::Uptime) => kernel().get_system_time() as usize,
+        Ok(SyscallNum::CurrentTaskId) => kernel().current_task_id(),
+        Ok(SyscallNum::CurrentTaskName) => {
+            let buf = unsafe { core::slice::from_raw_parts_mut(arg1 as *mut u8, arg2) };
+            kernel().current_task_name(buf)
+        }
+        Ok(SyscallNum::WaitForTask) => {
+            let exit_code = kernel().wait_for_task(TaskHandle::unpack(arg1));
+            Box::into_raw(Box::new(exit_code)) as usize
+        }
+        Ok(SyscallNum::Exit) => kernel().exit(arg1 as i32),
+        Ok(SyscallNum::ListTasks) => {
+            let buf = unsafe { core::slice::from_raw_parts_mut(arg1 as *mut system::task::TaskInfo, arg2) };
+            kernel().list_tasks(buf)
+        }
         Ok(SyscallNum::Yield) => {
             kernel().task_yield();
             0
@@ -63,7 +93,7 @@ pub fn handle_syscall(num: usize, arg1: usize, arg2: usize, arg3: usize) -> usiz
         }
         Ok(SyscallNum::Alloc) => {
             let Ok(layout) = Layout::from_size_align(arg1, arg2) else { return 0 };
-            (unsafe { services().memory_manager.alloc(layout) }) as usize
+            (unsafe { services().memory_manager.alloc_for_task(layout, kernel().current_task_id()) }) as usize
         }
         Ok(SyscallNum::Dealloc) => {
             let Ok(layout) = Layout::from_size_align(arg2, arg3) else { return 0 };
@@ -73,6 +103,32 @@ pub fn handle_syscall(num: usize, arg1: usize, arg2: usize, arg3: usize) -> usiz
         Ok(SyscallNum::TryReadChar) => {
             crate::keyboard::pop_key().map_or(0, |c| c as usize)
         }
+        Ok(SyscallNum::PeekChar) => {
+            crate::keyboard::peek_key().map_or(0, |c| c as usize)
+        }
+        Ok(SyscallNum::TerminalSize) => {
+            let (cols, rows) = crate::default_output::terminal_size();
+            ((cols as usize) << 16) | rows as usize
+        }
+        Ok(SyscallNum::PutCharAt) => {
+            crate::default_output::put_char_at(arg1 as u16, (arg1 >> 16) as u16, arg2 as u8, arg3 as u8, (arg3 >> 8) as u8);
+            0
+        }
+        Ok(SyscallNum::Flush) => {
+            crate::default_output::flush();
+            0
+        }
+        Ok(SyscallNum::ReadChars) => {
+            let buf = unsafe { core::slice::from_raw_parts_mut(arg1 as *mut char, arg2) };
+            crate::keyboard::pop_keys(buf)
+        }
+        Ok(SyscallNum::TryReadScancode) => {
+            crate::keyboard::pop_scancode().map_or(usize::MAX, |scancode| scancode as usize)
+        }
+        Ok(SyscallNum::ReadKernelLog) => {
+            let buf = unsafe { core::slice::from_raw_parts_mut(arg1 as *mut u8, arg2) };
+            crate::kernel_log::read(buf)
+        }
         Ok(SyscallNum::LoadElf) => {
             let elf_ptr = arg1;
             let elf_bytes: &[u8] = unsafe { *Box::from_raw(elf_ptr as *mut &[u8]) };
@@ -90,11 +146,54 @@ pub fn handle_syscall(num: usize, arg1: usize, arg2: usize, arg3: usize) -> usiz
             let value = arg3 as u32;
             let ipc_server_handle = IpcServerHandle::new(arg1 as HalfSize, arg2 as HalfSize);
             let message = IpcSendMessage { value };
-            let future_handle = services().ipc_manager.borrow_mut().send(ipc_server_handle, message);
+            let sender = kernel().execution_state.current_task.unwrap();
+            let future_handle = services().ipc_manager.borrow_mut().send(ipc_server_handle, sender, message);
             let future = kernel().wait_future(future_handle).unwrap();
             let ipc_reply_future = *future.as_any().downcast_ref::<IpcReplyFuture>().unwrap();
             Box::into_raw(Box::new(ipc_reply_future)) as usize
         }
+        Ok(SyscallNum::IpcNotify) => {
+            let value = arg3 as u32;
+            let ipc_server_handle = IpcServerHandle::new(arg1 as HalfSize, arg2 as HalfSize);
+            let message = IpcSendMessage { value };
+            let sender = kernel().execution_state.current_task.unwrap();
+            services().ipc_manager.borrow_mut().notify(ipc_server_handle, sender, message);
+            0
+        }
         Err(_) => 0,
     }
 }
+
+#[cfg(not(test))]
+fn sleep(ms: u64) {
+    let deadline = kernel().get_system_time() + ms;
+    kernel().sleep_until(deadline);
+}
+
+fn remaining_sleep(now: u64, deadline: u64) -> Option<u64> {
+    if deadline > now {
+        Some(deadline - now)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_sleep_is_none_when_deadline_has_passed() {
+        assert_eq!(remaining_sleep(100, 50), None);
+    }
+
+    #[test]
+    fn remaining_sleep_is_none_when_deadline_is_now() {
+        assert_eq!(remaining_sleep(100, 100), None);
+    }
+
+    #[test]
+    fn remaining_sleep_is_difference_when_deadline_is_in_the_future() {
+        assert_eq!(remaining_sleep(100, 250), Some(150));
+    }
+}
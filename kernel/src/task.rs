@@ -10,19 +10,23 @@ use system::future::FutureHandle;
 pub(crate) type TaskHandle = Handle;
 pub type SharedTask = Box<Task>;
 
+pub const DEFAULT_PRIORITY: u8 = 128;
+pub const MAX_TASK_NAME_LEN: usize = system::task::TASK_NAME_CAPACITY;
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum YieldReason {
     Voluntary,
     Preempted,
 }
 
+#[repr(u8)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub(crate) enum TaskState {
-    Created,
-    Ready,
-    Running,
-    Blocked,
-    Terminated,
+    Created = 0,
+    Ready = 1,
+    Running = 2,
+    Blocked = 3,
+    Terminated = 4,
 }
 
 impl Display for TaskState {
@@ -47,7 +51,8 @@ impl Display for TaskState {
     }
 }
 pub struct Task {
-    name: &'static str,
+    name: [u8; MAX_TASK_NAME_LEN],
+    name_len: u8,
     state: TaskState,
     yield_reason: Option<YieldReason>,
     stack_pointer: usize,
@@ -55,16 +60,29 @@ pub struct Task {
     entry_param: usize,
     stack: [usize; 2048], //16KB on 64bit systems
     completion_future: Option<FutureHandle>,
+    priority: u8,
+    exit_code: i32,
 }
 
 impl Task {
-    pub fn new<'a>(
-        name: &'static str,
+    pub fn new(
+        name: &str,
+        entry_point: usize,
+        entry_param: usize,
+    ) -> SharedTask {
+        Task::with_priority(name, entry_point, entry_param, DEFAULT_PRIORITY)
+    }
+
+    pub fn with_priority(
+        name: &str,
         entry_point: usize,
         entry_param: usize,
+        priority: u8,
     ) -> SharedTask {
+        let name_len = name.len().min(MAX_TASK_NAME_LEN);
         let mut task = Box::new(Task {
-            name,
+            name: [0; MAX_TASK_NAME_LEN],
+            name_len: name_len as u8,
             state: Created,
             yield_reason: None,
             stack_pointer: 0,
@@ -72,8 +90,12 @@ impl Task {
             entry_param,
             stack: [0; 2048],
             completion_future: None,
+            priority,
+            exit_code: 0,
         });
 
+        task.name[..name_len].copy_from_slice(&name.as_bytes()[..name_len]);
+
         unsafe {
             let stack_pointer = task.stack.as_mut_ptr().add(task.stack.len()).addr();
             task.set_stack_pointer(stack_pointer);
@@ -81,8 +103,8 @@ impl Task {
 
         task
     }
-    pub fn name(&self) -> &'static str {
-        self.name
+    pub fn name(&self) -> &str {
+        core::str::from_utf8(&self.name[..self.name_len as usize]).unwrap_or("?")
     }
     pub fn stack_pointer(&self) -> usize {
         self.stack_pointer
@@ -131,6 +153,26 @@ impl Task {
         self.completion_future = Some(handle);
     }
 
+    pub(crate) fn take_completion_future(&mut self) -> Option<FutureHandle> {
+        self.completion_future.take()
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+
+    pub fn set_exit_code(&mut self, exit_code: i32) {
+        self.exit_code = exit_code;
+    }
+
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: u8) {
+        self.priority = priority;
+    }
+
     pub fn entry_point(&self) -> usize {
         self.entry_point
     }
@@ -146,6 +188,11 @@ impl FunctionTask {
     pub fn new(name: &'static str, job: fn()) -> SharedTask {
         Task::new(name, task_wrapper as usize, job as usize)
     }
+
+    pub fn new_with_arg(name: &'static str, job: fn(usize), arg: usize) -> SharedTask {
+        let context = Box::into_raw(Box::new((job, arg))) as usize;
+        Task::new(name, arg_task_wrapper as *const () as usize, context)
+    }
 }
 
 pub fn idle_task_factory(cpu: &'static dyn Cpu) -> Box<Task> {
@@ -162,7 +209,11 @@ extern "C" fn idle_entry(cpu_ptr: usize) {
 }
 
 pub fn new_entrypoint_task(entrypoint: usize) -> SharedTask {
-    Task::new("EPT", task_wrapper as usize, entrypoint)
+    new_named_entrypoint_task("EPT", entrypoint)
+}
+
+pub fn new_named_entrypoint_task(name: &str, entrypoint: usize) -> SharedTask {
+    Task::new(name, task_wrapper as usize, entrypoint)
 }
 
 pub fn new_elf_task(elf: &'static [u8]) -> SharedTask {
@@ -180,6 +231,16 @@ pub(crate) extern "C" fn task_wrapper(entry_point: usize) {
     kernel().terminate_and_yield();
 }
 
+pub(crate) extern "C" fn arg_task_wrapper(context: usize) {
+    let (job, arg) = unsafe { *Box::from_raw(context as *mut (fn(usize), usize)) };
+
+    kernel().execution_state.preemption_enabled = true;
+
+    job(arg);
+
+    kernel().terminate_and_yield();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +264,36 @@ mod tests {
         task.set_completion_future(fh);
         assert_eq!(task.completion_future(), Some(fh));
     }
+
+    #[test]
+    fn new_task_has_default_priority() {
+        let task = Task::new("test", 0, 0);
+        assert_eq!(task.priority(), DEFAULT_PRIORITY);
+    }
+
+    #[test]
+    fn new_task_has_zero_exit_code() {
+        let task = Task::new("test", 0, 0);
+        assert_eq!(task.exit_code(), 0);
+    }
+
+    fn job_with_arg(_: usize) {}
+
+    #[test]
+    fn function_task_with_arg_stores_entry_and_arg() {
+        let task = FunctionTask::new_with_arg("arg", job_with_arg, 0xC0FFEE);
+
+        assert_eq!(task.entry_point(), arg_task_wrapper as *const () as usize);
+        let (job, arg) = unsafe { *Box::from_raw(task.entry_param() as *mut (fn(usize), usize)) };
+        assert_eq!(job as usize, job_with_arg as *const () as usize);
+        assert_eq!(arg, 0xC0FFEE);
+    }
+
+    #[test]
+    fn with_priority_sets_priority() {
+        let task = Task::with_priority("test", 0, 0, 200);
+        assert_eq!(task.priority(), 200);
+    }
 }
 
 pub(crate) extern "C" fn elf_task_wrapper(elf: usize) {
@@ -1,11 +1,16 @@
+use alloc::collections::VecDeque;
 use collections::generational_arena::GenerationalArena;
 use crate::task::TaskState::Terminated;
-use crate::task::{SharedTask, Task, TaskHandle, TaskState, YieldReason};
+use crate::task::{SharedTask, Task, TaskHandle, TaskState, YieldReason, DEFAULT_PRIORITY};
 use core::ptr::null_mut;
 use system::future::FutureHandle;
+use system::task::TaskInfo;
+
+const MAX_UNCOLLECTED_EXITS: usize = 16;
 
 pub(crate) struct TaskManager {
     tasks: GenerationalArena<SharedTask, 256>,
+    uncollected_exits: VecDeque<(TaskHandle, i32)>,
 }
 
 #[derive(Debug)]
@@ -18,6 +23,7 @@ impl TaskManager {
     pub(crate) fn new() -> Self {
         TaskManager {
             tasks: GenerationalArena::new(),
+            uncollected_exits: VecDeque::new(),
         }
     }
 
@@ -29,10 +35,42 @@ impl TaskManager {
     }
 
     pub(crate) fn remove_task(&mut self, handle: TaskHandle) {
-        let _ = self.tasks.remove(handle);
+        let Ok(task) = self.tasks.remove(handle) else { return };
+        if task.completion_future().is_some() {
+            if self.uncollected_exits.len() == MAX_UNCOLLECTED_EXITS {
+                self.uncollected_exits.pop_front();
+            }
+            self.uncollected_exits.push_back((handle, task.exit_code()));
+        }
+    }
+
+    pub(crate) fn collect_exit_code(&mut self, handle: TaskHandle) -> Option<i32> {
+        if let Ok(task) = self.tasks.borrow_mut(handle) {
+            if task.state() != Terminated {
+                return None;
+            }
+            return task.take_completion_future().map(|_| task.exit_code());
+        }
+        let index = self.uncollected_exits.iter().position(|&(exited, _)| exited == handle)?;
+        self.uncollected_exits.remove(index).map(|(_, exit_code)| exit_code)
     }
     
 
+    pub(crate) fn fill_task_info(&self, buf: &mut [TaskInfo]) -> usize {
+        let mut count = 0;
+        for ((handle, task), info) in self.tasks.iter().zip(buf.iter_mut()) {
+            let name = task.name().as_bytes();
+            let len = name.len().min(info.name.len());
+            *info = TaskInfo::empty();
+            info.id = handle.pack() as u64;
+            info.name[..len].copy_from_slice(&name[..len]);
+            info.name_len = len as u8;
+            info.state = task.state() as u8;
+            count += 1;
+        }
+        count
+    }
+
     pub(crate) fn borrow_task_mut(&mut self, handle: TaskHandle) -> Result<&mut Task, Error> {
         match self.tasks.borrow_mut(handle) {
             Ok(task) => Ok(task),
@@ -40,6 +78,13 @@ impl TaskManager {
         }
     }
 
+    pub(crate) fn get_name(&self, handle: TaskHandle) -> Option<&str> {
+        match self.tasks.borrow(handle) {
+            Ok(task) => Some(task.name()),
+            Err(_) => None,
+        }
+    }
+
     pub(crate) fn get_state(&self, handle: TaskHandle) -> TaskState {
         match self.tasks.borrow(handle) {
             Ok(task) => task.state(),
@@ -80,6 +125,27 @@ impl TaskManager {
         }
     }
 
+    pub(crate) fn get_priority(&self, handle: TaskHandle) -> u8 {
+        match self.tasks.borrow(handle) {
+            Ok(task) => task.priority(),
+            Err(_) => DEFAULT_PRIORITY,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_exit_code(&self, handle: TaskHandle) -> Option<i32> {
+        match self.tasks.borrow(handle) {
+            Ok(task) => Some(task.exit_code()),
+            Err(_) => None,
+        }
+    }
+
+    pub(crate) fn set_exit_code(&mut self, handle: TaskHandle, exit_code: i32) {
+        if let Ok(task) = self.tasks.borrow_mut(handle) {
+            task.set_exit_code(exit_code);
+        }
+    }
+
     pub(crate) fn get_completion_future(&self, handle: TaskHandle) -> Option<FutureHandle> {
         match self.tasks.borrow(handle) {
             Ok(task) => task.completion_future(),
@@ -122,4 +188,56 @@ mod tests {
         manager.set_completion_future(handle, fh);
         assert_eq!(manager.get_completion_future(handle), Some(fh));
     }
+
+    #[test]
+    fn exit_code_outlives_the_task_until_collected() {
+        let mut manager = TaskManager::new();
+        let handle = manager.add_task(Task::new("test", 0, 0)).unwrap();
+        manager.set_completion_future(handle, make_future_handle());
+        manager.set_exit_code(handle, 42);
+
+        manager.remove_task(handle);
+
+        assert_eq!(manager.collect_exit_code(handle), Some(42));
+        assert_eq!(manager.collect_exit_code(handle), None);
+    }
+
+    #[test]
+    fn collected_task_leaves_no_exit_code_behind() {
+        let mut manager = TaskManager::new();
+        let handle = manager.add_task(Task::new("test", 0, 0)).unwrap();
+        manager.set_completion_future(handle, make_future_handle());
+        manager.set_exit_code(handle, 3);
+        manager.set_state(handle, Terminated);
+
+        assert_eq!(manager.collect_exit_code(handle), Some(3));
+        manager.remove_task(handle);
+
+        assert_eq!(manager.collect_exit_code(handle), None);
+    }
+
+    #[test]
+    fn running_task_has_no_exit_code_to_collect() {
+        let mut manager = TaskManager::new();
+        let handle = manager.add_task(Task::new("test", 0, 0)).unwrap();
+        manager.set_completion_future(handle, make_future_handle());
+
+        assert_eq!(manager.collect_exit_code(handle), None);
+        assert_eq!(manager.get_completion_future(handle), Some(make_future_handle()));
+    }
+
+    #[test]
+    fn uncollected_exit_codes_are_bounded() {
+        let mut manager = TaskManager::new();
+        let mut handles = Vec::new();
+        for _ in 0..MAX_UNCOLLECTED_EXITS + 1 {
+            let handle = manager.add_task(Task::new("test", 0, 0)).unwrap();
+            manager.set_completion_future(handle, make_future_handle());
+            manager.remove_task(handle);
+            handles.push(handle);
+        }
+
+        assert_eq!(manager.collect_exit_code(handles[0]), None);
+        assert_eq!(manager.collect_exit_code(handles[MAX_UNCOLLECTED_EXITS]), Some(0));
+    }
 }
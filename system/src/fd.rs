@@ -0,0 +1,2 @@
+pub const STDOUT: usize = 1;
+pub const STDERR: usize = 2;
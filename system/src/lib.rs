@@ -3,6 +3,8 @@ extern crate alloc;
 extern crate collections;
 
 pub mod syscall_numbers;
+pub mod fd;
 pub mod future;
 pub mod ipc;
+pub mod task;
 
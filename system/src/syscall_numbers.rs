@@ -14,6 +14,24 @@ pub enum SyscallNum {
     LoadElf = 10,
     IpcFind = 11,
     IpcSend = 12,
+    IpcNotify = 13,
+    SleepUntil = 14,
+    Uptime = 15,
+    CurrentTaskId = 16,
+    CurrentTaskName = 17,
+    WaitForTask = 18,
+    Spawn = 19,
+    Kill = 20,
+    Exit = 21,
+    ListTasks = 22,
+    TryReadScancode = 23,
+    ReadKernelLog = 24,
+    Write = 25,
+    ReadChars = 26,
+    TerminalSize = 27,
+    PutCharAt = 28,
+    Flush = 29,
+    PeekChar = 30,
 }
 
 impl TryFrom<usize> for SyscallNum {
@@ -34,6 +52,24 @@ impl TryFrom<usize> for SyscallNum {
             10 => Ok(Self::LoadElf),
             11 => Ok(Self::IpcFind),
             12 => Ok(Self::IpcSend),
+            13 => Ok(Self::IpcNotify),
+            14 => Ok(Self::SleepUntil),
+            15 => Ok(Self::Uptime),
+            16 => Ok(Self::CurrentTaskId),
+            17 => Ok(Self::CurrentTaskName),
+            18 => Ok(Self::WaitForTask),
+            19 => Ok(Self::Spawn),
+            20 => Ok(Self::Kill),
+            21 => Ok(Self::Exit),
+            22 => Ok(Self::ListTasks),
+            23 => Ok(Self::TryReadScancode),
+            24 => Ok(Self::ReadKernelLog),
+            25 => Ok(Self::Write),
+            26 => Ok(Self::ReadChars),
+            27 => Ok(Self::TerminalSize),
+            28 => Ok(Self::PutCharAt),
+            29 => Ok(Self::Flush),
+            30 => Ok(Self::PeekChar),
             _ => Err(()),
         }
     }
@@ -0,0 +1,59 @@
+pub const TASK_NAME_CAPACITY: usize = 32;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub name: [u8; TASK_NAME_CAPACITY],
+    pub name_len: u8,
+    pub state: u8,
+}
+
+impl TaskInfo {
+    pub const fn empty() -> Self {
+        TaskInfo {
+            id: 0,
+            name: [0; TASK_NAME_CAPACITY],
+            name_len: 0,
+            state: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        let len = (self.name_len as usize).min(TASK_NAME_CAPACITY);
+        core::str::from_utf8(&self.name[..len]).unwrap_or("?")
+    }
+
+    pub fn state_name(&self) -> &'static str {
+        match self.state {
+            0 => "Created",
+            1 => "Ready",
+            2 => "Running",
+            3 => "Blocked",
+            4 => "Terminated",
+            _ => "Unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_returns_only_filled_bytes() {
+        let mut info = TaskInfo::empty();
+        info.name[..5].copy_from_slice(b"Shell");
+        info.name_len = 5;
+        assert_eq!(info.name(), "Shell");
+    }
+
+    #[test]
+    fn state_name_maps_known_and_unknown_states() {
+        let mut info = TaskInfo::empty();
+        info.state = 3;
+        assert_eq!(info.state_name(), "Blocked");
+        info.state = 9;
+        assert_eq!(info.state_name(), "Unknown");
+    }
+}
@@ -0,0 +1,554 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+const ANSI_COLORS: [AnsiColor; 16] = [
+    AnsiColor::Black,
+    AnsiColor::Red,
+    AnsiColor::Green,
+    AnsiColor::Yellow,
+    AnsiColor::Blue,
+    AnsiColor::Magenta,
+    AnsiColor::Cyan,
+    AnsiColor::White,
+    AnsiColor::BrightBlack,
+    AnsiColor::BrightRed,
+    AnsiColor::BrightGreen,
+    AnsiColor::BrightYellow,
+    AnsiColor::BrightBlue,
+    AnsiColor::BrightMagenta,
+    AnsiColor::BrightCyan,
+    AnsiColor::BrightWhite,
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl AnsiColor {
+    pub const fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            AnsiColor::Black         => (0,   0,   0),
+            AnsiColor::Red           => (170, 0,   0),
+            AnsiColor::Green         => (0,   170, 0),
+            AnsiColor::Yellow        => (170, 170, 0),
+            AnsiColor::Blue          => (0,   0,   170),
+            AnsiColor::Magenta       => (170, 0,   170),
+            AnsiColor::Cyan          => (0,   170, 170),
+            AnsiColor::White         => (170, 170, 170),
+            AnsiColor::BrightBlack   => (85,  85,  85),
+            AnsiColor::BrightRed     => (255, 85,  85),
+            AnsiColor::BrightGreen   => (85,  255, 85),
+            AnsiColor::BrightYellow  => (255, 255, 85),
+            AnsiColor::BrightBlue    => (85,  85,  255),
+            AnsiColor::BrightMagenta => (255, 85,  255),
+            AnsiColor::BrightCyan    => (85,  255, 255),
+            AnsiColor::BrightWhite   => (255, 255, 255),
+        }
+    }
+
+    pub fn nearest(rgb: (u8, u8, u8)) -> AnsiColor {
+        let distance = |color: &AnsiColor| {
+            let (r, g, b) = color.rgb();
+            let dr = r as i32 - rgb.0 as i32;
+            let dg = g as i32 - rgb.1 as i32;
+            let db = b as i32 - rgb.2 as i32;
+            dr * dr + dg * dg + db * db
+        };
+        *ANSI_COLORS.iter().min_by_key(|color| distance(color)).unwrap()
+    }
+}
+
+pub fn xterm_256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_COLORS[index as usize].rgb(),
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[((cube / 6) % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiState {
+    Normal,
+    Escape,
+    Csi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClearMode {
+    ToEnd,
+    ToStart,
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiCommand {
+    PrintChar(u8),
+    SetForeground(AnsiColor),
+    SetBackground(AnsiColor),
+    SetForeground256(u8),
+    SetBackground256(u8),
+    SetForegroundRgb(u8, u8, u8),
+    SetBackgroundRgb(u8, u8, u8),
+    ResetAttributes,
+    SetBold(bool),
+    SetReverse(bool),
+    SetCursorPos { row: usize, col: usize },
+    ClearScreen(ClearMode),
+    ClearLine(ClearMode),
+    SaveCursor,
+    RestoreCursor,
+    SetScrollRegion { top: usize, bottom: usize },
+    MoveCursor { dx: isize, dy: isize },
+    AlternateScreen(bool),
+}
+
+const MAX_PARAMS: usize = 16;
+
+pub struct AnsiParser {
+    state: AnsiState,
+    params: [u16; MAX_PARAMS],
+    param_idx: usize,
+    current_param: u16,
+    has_param: bool,
+    private: bool,
+    command_queue: [Option<AnsiCommand>; MAX_PARAMS + 1],
+    queue_head: usize,
+    queue_tail: usize,
+}
+
+impl AnsiParser {
+    pub const fn new() -> Self {
+        Self {
+            state: AnsiState::Normal,
+            params: [0; MAX_PARAMS],
+            param_idx: 0,
+            current_param: 0,
+            has_param: false,
+            private: false,
+            command_queue: [None; MAX_PARAMS + 1],
+            queue_head: 0,
+            queue_tail: 0,
+        }
+    }
+
+    fn push_command(&mut self, command: AnsiCommand) {
+        let next_tail = (self.queue_tail + 1) % self.command_queue.len();
+        if next_tail != self.queue_head {
+            self.command_queue[self.queue_tail] = Some(command);
+            self.queue_tail = next_tail;
+        }
+    }
+
+    fn push_param(&mut self) {
+        if self.param_idx < self.params.len() {
+            self.params[self.param_idx] = self.current_param;
+            self.param_idx += 1;
+        }
+        self.current_param = 0;
+        self.has_param = false;
+    }
+
+    pub fn handle_byte(&mut self, byte: u8) {
+        match self.state {
+            AnsiState::Normal => {
+                if byte == 0x1B {
+                    self.state = AnsiState::Escape;
+                } else {
+                    self.push_command(AnsiCommand::PrintChar(byte));
+                }
+            }
+            AnsiState::Escape => {
+                if byte == b'[' {
+                    self.state = AnsiState::Csi;
+                    self.params = [0; MAX_PARAMS];
+                    self.param_idx = 0;
+                    self.current_param = 0;
+                    self.has_param = false;
+                    self.private = false;
+                } else {
+                    self.state = AnsiState::Normal;
+                    self.push_command(AnsiCommand::PrintChar(byte));
+                }
+            }
+            AnsiState::Csi => {
+                match byte {
+                    b'0'..=b'9' => {
+                        self.current_param = self.current_param.saturating_mul(10).saturating_add((byte - b'0') as u16);
+                        self.has_param = true;
+                    }
+                    b';' => self.push_param(),
+                    b'?' => self.private = true,
+                    b'm' => {
+                        // SGR - Select Graphic Rendition
+                        if self.has_param {
+                            self.push_param();
+                        }
+                        
+                        if self.param_idx == 0 {
+                             self.push_command(AnsiCommand::ResetAttributes);
+                        } else {
+                            let mut i = 0;
+                            while i < self.param_idx {
+                                let param = self.params[i];
+                                match param {
+                                    0 => self.push_command(AnsiCommand::ResetAttributes),
+                                    1 => self.push_command(AnsiCommand::SetBold(true)),
+                                    7 => self.push_command(AnsiCommand::SetReverse(true)),
+                                    22 => self.push_command(AnsiCommand::SetBold(false)),
+                                    27 => self.push_command(AnsiCommand::SetReverse(false)),
+                                    30..=37 => self.push_command(AnsiCommand::SetForeground(self.ansi_color(param - 30))),
+                                    40..=47 => self.push_command(AnsiCommand::SetBackground(self.ansi_color(param - 40))),
+                                    90..=97 => self.push_command(AnsiCommand::SetForeground(self.ansi_bright_color(param - 90))),
+                                    100..=107 => self.push_command(AnsiCommand::SetBackground(self.ansi_bright_color(param - 100))),
+                                    38 | 48 if i + 2 < self.param_idx && self.params[i + 1] == 5 => {
+                                        let index = self.params[i + 2].min(255) as u8;
+                                        if param == 38 {
+                                            self.push_command(AnsiCommand::SetForeground256(index));
+                                        } else {
+                                            self.push_command(AnsiCommand::SetBackground256(index));
+                                        }
+                                        i += 2;
+                                    }
+                                    38 | 48 if i + 4 < self.param_idx && self.params[i + 1] == 2 => {
+                                        let r = self.params[i + 2].min(255) as u8;
+                                        let g = self.params[i + 3].min(255) as u8;
+                                        let b = self.params[i + 4].min(255) as u8;
+                                        if param == 38 {
+                                            self.push_command(AnsiCommand::SetForegroundRgb(r, g, b));
+                                        } else {
+                                            self.push_command(AnsiCommand::SetBackgroundRgb(r, g, b));
+                                        }
+                                        i += 4;
+                                    }
+                                    _ => {}
+                                }
+                                i += 1;
+                            }
+                        }
+                        self.state = AnsiState::Normal;
+                    }
+                    b'H' | b'f' => {
+                        // CUP - Cursor Position
+                        if self.has_param {
+                            self.push_param();
+                        }
+                        let row = if self.param_idx > 0 { self.params[0].saturating_sub(1) as usize } else { 0 };
+                        let col = if self.param_idx > 1 { self.params[1].saturating_sub(1) as usize } else { 0 };
+                        self.push_command(AnsiCommand::SetCursorPos { row, col });
+                        self.state = AnsiState::Normal;
+                    }
+                    b'A' | b'B' | b'C' | b'D' => {
+                        // CUU/CUD/CUF/CUB - Cursor Up/Down/Forward/Back
+                        let count = if self.has_param && self.current_param > 0 { self.current_param as isize } else { 1 };
+                        let (dx, dy) = match byte {
+                            b'A' => (0, -count),
+                            b'B' => (0, count),
+                            b'C' => (count, 0),
+                            _ => (-count, 0),
+                        };
+                        self.push_command(AnsiCommand::MoveCursor { dx, dy });
+                        self.state = AnsiState::Normal;
+                    }
+                    b'J' => {
+                        // ED - Erase Display
+                        if let Some(mode) = self.clear_mode(3) {
+                            self.push_command(AnsiCommand::ClearScreen(mode));
+                        }
+                        self.state = AnsiState::Normal;
+                    }
+                    b'K' => {
+                        // EL - Erase Line
+                        if let Some(mode) = self.clear_mode(2) {
+                            self.push_command(AnsiCommand::ClearLine(mode));
+                        }
+                        self.state = AnsiState::Normal;
+                    }
+                    b'r' => {
+                        // DECSTBM - Set Top and Bottom Margins
+                        if self.has_param {
+                            self.push_param();
+                        }
+                        let top = if self.param_idx > 0 { self.params[0].saturating_sub(1) as usize } else { 0 };
+                        let bottom = if self.param_idx > 1 && self.params[1] > 0 { self.params[1] as usize - 1 } else { usize::MAX };
+                        self.push_command(AnsiCommand::SetScrollRegion { top, bottom });
+                        self.state = AnsiState::Normal;
+                    }
+                    b'h' | b'l' => {
+                        // DECSET/DECRST - Private Mode Set/Reset
+                        if self.private && self.current_param == 1049 {
+                            self.push_command(AnsiCommand::AlternateScreen(byte == b'h'));
+                        }
+                        self.state = AnsiState::Normal;
+                    }
+                    b's' => {
+                        // SCP - Save Cursor Position
+                        self.push_command(AnsiCommand::SaveCursor);
+                        self.state = AnsiState::Normal;
+                    }
+                    b'u' => {
+                        // RCP - Restore Cursor Position
+                        self.push_command(AnsiCommand::RestoreCursor);
+                        self.state = AnsiState::Normal;
+                    }
+                    _ => {
+                        // Unknown or unsupported sequence
+                        self.state = AnsiState::Normal;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn next_command(&mut self) -> Option<AnsiCommand> {
+        if self.queue_head == self.queue_tail {
+            None
+        } else {
+            let command = self.command_queue[self.queue_head].take();
+            self.queue_head = (self.queue_head + 1) % self.command_queue.len();
+            command
+        }
+    }
+
+    fn clear_mode(&self, max_param: u16) -> Option<ClearMode> {
+        match self.current_param {
+            0 => Some(ClearMode::ToEnd),
+            1 => Some(ClearMode::ToStart),
+            n if n <= max_param => Some(ClearMode::All),
+            _ => None,
+        }
+    }
+
+    const fn ansi_color(&self, code: u16) -> AnsiColor {
+        match code {
+            0 => AnsiColor::Black,
+            1 => AnsiColor::Red,
+            2 => AnsiColor::Green,
+            3 => AnsiColor::Yellow,
+            4 => AnsiColor::Blue,
+            5 => AnsiColor::Magenta,
+            6 => AnsiColor::Cyan,
+            7 => AnsiColor::White,
+            _ => AnsiColor::White,
+        }
+    }
+
+    const fn ansi_bright_color(&self, code: u16) -> AnsiColor {
+        match code {
+            0 => AnsiColor::BrightBlack,
+            1 => AnsiColor::BrightRed,
+            2 => AnsiColor::BrightGreen,
+            3 => AnsiColor::BrightYellow,
+            4 => AnsiColor::BrightBlue,
+            5 => AnsiColor::BrightMagenta,
+            6 => AnsiColor::BrightCyan,
+            7 => AnsiColor::BrightWhite,
+            _ => AnsiColor::BrightWhite,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    fn commands(input: &[u8]) -> Vec<AnsiCommand> {
+        let mut parser = AnsiParser::new();
+        let mut commands = Vec::new();
+        for &byte in input {
+            parser.handle_byte(byte);
+            while let Some(command) = parser.next_command() {
+                commands.push(command);
+            }
+        }
+        commands
+    }
+
+    #[test]
+    fn parses_save_cursor() {
+        assert_eq!(commands(b"\x1B[s"), [AnsiCommand::SaveCursor]);
+    }
+
+    #[test]
+    fn parses_restore_cursor() {
+        assert_eq!(commands(b"\x1B[u"), [AnsiCommand::RestoreCursor]);
+    }
+
+    #[test]
+    fn parses_default_scroll_region() {
+        assert_eq!(commands(b"\x1B[r"), [AnsiCommand::SetScrollRegion { top: 0, bottom: usize::MAX }]);
+    }
+
+    #[test]
+    fn parses_explicit_scroll_region() {
+        assert_eq!(commands(b"\x1B[3;20r"), [AnsiCommand::SetScrollRegion { top: 2, bottom: 19 }]);
+    }
+
+    #[test]
+    fn parses_relative_moves_with_default_count() {
+        assert_eq!(
+            commands(b"\x1B[A\x1B[B\x1B[C\x1B[D"),
+            [
+                AnsiCommand::MoveCursor { dx: 0, dy: -1 },
+                AnsiCommand::MoveCursor { dx: 0, dy: 1 },
+                AnsiCommand::MoveCursor { dx: 1, dy: 0 },
+                AnsiCommand::MoveCursor { dx: -1, dy: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_relative_moves_with_explicit_count() {
+        assert_eq!(
+            commands(b"\x1B[3A\x1B[4B\x1B[9C\x1B[12D"),
+            [
+                AnsiCommand::MoveCursor { dx: 0, dy: -3 },
+                AnsiCommand::MoveCursor { dx: 0, dy: 4 },
+                AnsiCommand::MoveCursor { dx: 9, dy: 0 },
+                AnsiCommand::MoveCursor { dx: -12, dy: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_256_color_foreground_and_background() {
+        assert_eq!(
+            commands(b"\x1B[38;5;196m\x1B[48;5;21m"),
+            [AnsiCommand::SetForeground256(196), AnsiCommand::SetBackground256(21)]
+        );
+    }
+
+    #[test]
+    fn xterm_palette_maps_known_indices_to_rgb() {
+        assert_eq!(xterm_256_to_rgb(1), (170, 0, 0));
+        assert_eq!(xterm_256_to_rgb(16), (0, 0, 0));
+        assert_eq!(xterm_256_to_rgb(196), (255, 0, 0));
+        assert_eq!(xterm_256_to_rgb(110), (135, 175, 215));
+        assert_eq!(xterm_256_to_rgb(231), (255, 255, 255));
+        assert_eq!(xterm_256_to_rgb(232), (8, 8, 8));
+        assert_eq!(xterm_256_to_rgb(255), (238, 238, 238));
+    }
+
+    #[test]
+    fn parses_truecolor_foreground_and_background() {
+        assert_eq!(
+            commands(b"\x1B[38;2;10;20;30;48;2;200;100;50m"),
+            [AnsiCommand::SetForegroundRgb(10, 20, 30), AnsiCommand::SetBackgroundRgb(200, 100, 50)]
+        );
+    }
+
+    #[test]
+    fn truecolor_components_saturate_at_255() {
+        assert_eq!(commands(b"\x1B[38;2;300;0;999m"), [AnsiCommand::SetForegroundRgb(255, 0, 255)]);
+    }
+
+    #[test]
+    fn nearest_picks_closest_ansi_color() {
+        assert_eq!(AnsiColor::nearest((0, 0, 0)), AnsiColor::Black);
+        assert_eq!(AnsiColor::nearest((250, 240, 245)), AnsiColor::BrightWhite);
+        assert_eq!(AnsiColor::nearest((160, 10, 5)), AnsiColor::Red);
+        assert_eq!(AnsiColor::nearest((90, 250, 80)), AnsiColor::BrightGreen);
+    }
+
+    #[test]
+    fn parses_bold_and_reverse_toggles() {
+        assert_eq!(commands(b"\x1B[1m"), [AnsiCommand::SetBold(true)]);
+        assert_eq!(commands(b"\x1B[7m"), [AnsiCommand::SetReverse(true)]);
+        assert_eq!(commands(b"\x1B[22m"), [AnsiCommand::SetBold(false)]);
+        assert_eq!(commands(b"\x1B[27m"), [AnsiCommand::SetReverse(false)]);
+    }
+
+    #[test]
+    fn sgr_with_maximum_parameter_count_emits_every_attribute() {
+        let parsed = commands(b"\x1B[1;7;22;27;30;31;32;33;34;35;36;37;40;41;42;43m");
+
+        assert_eq!(parsed.len(), 16);
+        assert_eq!(parsed[0], AnsiCommand::SetBold(true));
+        assert_eq!(parsed[15], AnsiCommand::SetBackground(AnsiColor::Yellow));
+    }
+
+    #[test]
+    fn sgr_keeps_attributes_after_extended_colors() {
+        assert_eq!(
+            commands(b"\x1B[1;7;38;5;196;48;2;1;2;3;4m"),
+            [
+                AnsiCommand::SetBold(true),
+                AnsiCommand::SetReverse(true),
+                AnsiCommand::SetForeground256(196),
+                AnsiCommand::SetBackgroundRgb(1, 2, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_combined_sgr_in_order() {
+        assert_eq!(
+            commands(b"\x1B[0;32;40m"),
+            [
+                AnsiCommand::ResetAttributes,
+                AnsiCommand::SetForeground(AnsiColor::Green),
+                AnsiCommand::SetBackground(AnsiColor::Black),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_erase_display_variants() {
+        assert_eq!(commands(b"\x1B[J"), [AnsiCommand::ClearScreen(ClearMode::ToEnd)]);
+        assert_eq!(commands(b"\x1B[0J"), [AnsiCommand::ClearScreen(ClearMode::ToEnd)]);
+        assert_eq!(commands(b"\x1B[1J"), [AnsiCommand::ClearScreen(ClearMode::ToStart)]);
+        assert_eq!(commands(b"\x1B[2J"), [AnsiCommand::ClearScreen(ClearMode::All)]);
+        assert_eq!(commands(b"\x1B[3J"), [AnsiCommand::ClearScreen(ClearMode::All)]);
+        assert_eq!(commands(b"\x1B[4J"), []);
+    }
+
+    #[test]
+    fn parses_erase_line_variants() {
+        assert_eq!(commands(b"\x1B[K"), [AnsiCommand::ClearLine(ClearMode::ToEnd)]);
+        assert_eq!(commands(b"\x1B[0K"), [AnsiCommand::ClearLine(ClearMode::ToEnd)]);
+        assert_eq!(commands(b"\x1B[1K"), [AnsiCommand::ClearLine(ClearMode::ToStart)]);
+        assert_eq!(commands(b"\x1B[2K"), [AnsiCommand::ClearLine(ClearMode::All)]);
+        assert_eq!(commands(b"\x1B[3K"), []);
+    }
+
+    #[test]
+    fn parses_alternate_screen_private_mode() {
+        assert_eq!(commands(b"\x1B[?1049h"), [AnsiCommand::AlternateScreen(true)]);
+        assert_eq!(commands(b"\x1B[?1049l"), [AnsiCommand::AlternateScreen(false)]);
+    }
+
+    #[test]
+    fn ignores_other_private_modes() {
+        assert_eq!(commands(b"\x1B[?25l"), []);
+        assert_eq!(commands(b"\x1B[1049h"), []);
+    }
+
+    #[test]
+    fn private_flag_does_not_leak_into_next_sequence() {
+        assert_eq!(commands(b"\x1B[?25h\x1B[1049h"), []);
+    }
+}
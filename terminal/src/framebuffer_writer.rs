@@ -0,0 +1,875 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ptr::null_mut;
+use crate::ansi_parser::{xterm_256_to_rgb, AnsiCommand, AnsiParser, ClearMode};
+use crate::fonts::{BitmapFont, TERMINUS_8X16};
+
+const MAX_WORD: usize = 64;
+const BLANK: Cell = (b' ', (0, 0, 0), (0, 0, 0));
+
+pub type Cell = (u8, (u8, u8, u8), (u8, u8, u8));
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WrapMode {
+    CharWrap,
+    WordWrap,
+    Truncate,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FrameInfo {
+    pub width: usize,
+    pub height: usize,
+    pub stride: usize,
+    pub bytes_per_pixel: usize,
+    pub bgr: bool,
+}
+
+pub struct Surface {
+    front: *mut u8,
+    info: FrameInfo,
+    back: Vec<u8>,
+    dirty_start: usize,
+    dirty_end: usize,
+}
+
+// SAFETY: the surface is the only writer of the pixel memory it points to, and it
+// is only reachable through the owning writer.
+unsafe impl Send for Surface {}
+
+impl Surface {
+    pub const fn empty() -> Self {
+        Surface {
+            front: null_mut(),
+            info: FrameInfo { width: 0, height: 0, stride: 0, bytes_per_pixel: 4, bgr: false },
+            back: Vec::new(),
+            dirty_start: usize::MAX,
+            dirty_end: 0,
+        }
+    }
+
+    /// # Safety
+    /// `front` must stay valid for writes of `stride * height * bytes_per_pixel` bytes
+    /// for as long as the surface is alive.
+    pub unsafe fn new(front: *mut u8, info: FrameInfo) -> Self {
+        Surface { front, info, ..Surface::empty() }
+    }
+
+    pub fn enable_double_buffering(&mut self) -> bool {
+        if self.front.is_null() { return false; }
+        if !self.back.is_empty() { return true; }
+        let bytes = self.frame_bytes();
+        let mut back = Vec::new();
+        if back.try_reserve_exact(bytes).is_err() { return false; }
+        back.resize(bytes, 0u8);
+        unsafe { core::ptr::copy_nonoverlapping(self.front, back.as_mut_ptr(), bytes); }
+        self.back = back;
+        true
+    }
+
+    pub fn present(&mut self) {
+        if self.back.is_empty() { return; }
+        let from = core::mem::replace(&mut self.dirty_start, usize::MAX);
+        let to = core::mem::replace(&mut self.dirty_end, 0).min(self.frame_bytes());
+        if from >= to { return; }
+        unsafe { core::ptr::copy_nonoverlapping(self.back.as_ptr().add(from), self.front.add(from), to - from); }
+    }
+
+    fn frame_bytes(&self) -> usize {
+        self.info.height * self.info.stride * self.info.bytes_per_pixel
+    }
+
+    fn target(&mut self) -> *mut u8 {
+        if self.back.is_empty() { self.front } else { self.back.as_mut_ptr() }
+    }
+
+    fn mark_dirty(&mut self, from: usize, to: usize) {
+        self.dirty_start = self.dirty_start.min(from);
+        self.dirty_end = self.dirty_end.max(to);
+    }
+
+    fn clear(&mut self) {
+        let start = self.target();
+        if start.is_null() { return; }
+        let bytes = self.frame_bytes();
+        unsafe { core::ptr::write_bytes(start, 0, bytes); }
+        self.mark_dirty(0, bytes);
+    }
+
+    fn draw_char(&mut self, font: &BitmapFont, col: usize, row: usize, ch: u8, fg: (u8, u8, u8), bg: (u8, u8, u8)) {
+        let start = self.target();
+        if start.is_null() { return; }
+        let stride = self.info.stride;
+        let bpp    = self.info.bytes_per_pixel;
+        let glyph  = font.glyph(ch);
+        let base_x = col * font.char_w;
+        let base_y = row * font.char_h;
+        for (bit_y, &row_bits) in glyph.iter().enumerate() {
+            let row_base = (base_y + bit_y) * stride * bpp;
+            for bit_x in 0..font.char_w {
+                let (r, g, b) = if (row_bits >> (7 - bit_x)) & 1 != 0 { fg } else { bg };
+                let off = row_base + (base_x + bit_x) * bpp;
+                unsafe {
+                    let ptr = start.add(off);
+                    if self.info.bgr { ptr.write(b); ptr.add(1).write(g); ptr.add(2).write(r); }
+                    else             { ptr.write(r); ptr.add(1).write(g); ptr.add(2).write(b); }
+                }
+            }
+        }
+        self.mark_dirty(base_y * stride * bpp, (base_y + font.char_h) * stride * bpp);
+    }
+
+    fn invert_cell(&mut self, font: &BitmapFont, col: usize, row: usize) {
+        let start = self.target();
+        if start.is_null() { return; }
+        let stride = self.info.stride;
+        let bpp    = self.info.bytes_per_pixel;
+        let base_x = col * font.char_w;
+        let base_y = row * font.char_h;
+        for bit_y in 0..font.char_h {
+            let row_base = (base_y + bit_y) * stride * bpp;
+            for bit_x in 0..font.char_w {
+                let off = row_base + (base_x + bit_x) * bpp;
+                unsafe {
+                    let ptr = start.add(off);
+                    for i in 0..3 { ptr.add(i).write(!ptr.add(i).read()); }
+                }
+            }
+        }
+        self.mark_dirty(base_y * stride * bpp, (base_y + font.char_h) * stride * bpp);
+    }
+
+    fn invert(&mut self) {
+        let start = self.target();
+        if start.is_null() { return; }
+        let bpp    = self.info.bytes_per_pixel;
+        let pixels = self.info.height * self.info.stride;
+        for pixel in 0..pixels {
+            unsafe {
+                let ptr = start.add(pixel * bpp);
+                for i in 0..3 { ptr.add(i).write(!ptr.add(i).read()); }
+            }
+        }
+        self.mark_dirty(0, pixels * bpp);
+    }
+
+    fn scroll_up(&mut self, font: &BitmapFont, top: usize, bottom: usize) {
+        let start  = self.target();
+        if start.is_null() { return; }
+        let row_bytes    = self.info.stride * self.info.bytes_per_pixel;
+        let scroll_bytes = font.char_h * row_bytes;
+        let region_start = top * scroll_bytes;
+        let region_bytes = (bottom + 1 - top) * scroll_bytes;
+        unsafe {
+            let region = start.add(region_start);
+            core::ptr::copy(region.add(scroll_bytes), region, region_bytes - scroll_bytes);
+            core::ptr::write_bytes(region.add(region_bytes - scroll_bytes), 0, scroll_bytes);
+        }
+        self.mark_dirty(region_start, region_start + region_bytes);
+    }
+}
+
+fn brighten((r, g, b): (u8, u8, u8)) -> (u8, u8, u8) {
+    (r.saturating_add(85), g.saturating_add(85), b.saturating_add(85))
+}
+
+pub struct FramebufferWriter {
+    surface:    Surface,
+    font:       &'static BitmapFont,
+    col:        usize,
+    row:        usize,
+    saved:      (usize, usize),
+    cursor_enabled: bool,
+    cursor_at:  Option<(usize, usize)>,
+    scroll_top:    usize,
+    scroll_bottom: usize,
+    text_cols:  usize,
+    text_rows:  usize,
+    fg:         (u8, u8, u8),
+    bg:         (u8, u8, u8),
+    default_fg: (u8, u8, u8),
+    default_bg: (u8, u8, u8),
+    bold:       bool,
+    reverse:    bool,
+    bell_pending: bool,
+    written_since_tick: bool,
+    wrap_mode:  WrapMode,
+    word:       [Cell; MAX_WORD],
+    cells:      Vec<Cell>,
+    word_len:   usize,
+    ansi_parser: AnsiParser,
+}
+
+impl FramebufferWriter {
+    pub fn new(surface: Surface) -> Self {
+        let fg = (0, 255, 0);
+        let bg = (0, 0, 0);
+        let font = &TERMINUS_8X16;
+        let text_rows = surface.info.height / font.char_h;
+        let text_cols = surface.info.width / font.char_w;
+        FramebufferWriter {
+            surface,
+            font,
+            col: 0,
+            row: 0,
+            saved: (0, 0),
+            cursor_enabled: true,
+            cursor_at: None,
+            scroll_top: 0,
+            scroll_bottom: text_rows.saturating_sub(1),
+            text_cols,
+            text_rows,
+            fg,
+            bg,
+            default_fg: fg,
+            default_bg: bg,
+            bold: false,
+            reverse: false,
+            bell_pending: false,
+            written_since_tick: false,
+            wrap_mode: WrapMode::CharWrap,
+            word: [(0, fg, bg); MAX_WORD],
+            cells: Vec::new(),
+            word_len: 0,
+            ansi_parser: AnsiParser::new(),
+        }
+    }
+
+    pub fn text_size(&self) -> (usize, usize) {
+        (self.text_cols, self.text_rows)
+    }
+
+    pub fn set_font(&mut self, font: &'static BitmapFont) {
+        self.bell_pending = false;
+        self.cursor_at = None;
+        self.font = font;
+        self.text_cols = self.surface.info.width / font.char_w;
+        self.text_rows = self.surface.info.height / font.char_h;
+        if !self.cells.is_empty() {
+            self.cells.clear();
+            self.cells.resize(self.text_cols * self.text_rows, BLANK);
+        }
+        self.clear();
+        self.scroll_top = 0;
+        self.scroll_bottom = self.text_rows.saturating_sub(1);
+        self.row = 0;
+        self.col = 0;
+        self.saved = (0, 0);
+        self.word_len = 0;
+        self.draw_cursor();
+    }
+
+    pub fn set_wrap_mode(&mut self, mode: WrapMode) {
+        self.wrap_mode = mode;
+    }
+
+    pub fn set_default_colors(&mut self, fg: (u8, u8, u8), bg: (u8, u8, u8)) {
+        self.default_fg = fg;
+        self.default_bg = bg;
+        self.fg = fg;
+        self.bg = bg;
+    }
+
+    pub fn put_char_at(&mut self, col: usize, row: usize, ch: u8, fg: (u8, u8, u8), bg: (u8, u8, u8)) {
+        if row < self.text_rows && col < self.text_cols {
+            self.end_bell();
+            self.erase_cursor();
+            self.draw(col, row, ch, fg, bg);
+            self.draw_cursor();
+            self.written_since_tick = true;
+        }
+    }
+
+    pub fn enable_cursor(&mut self, enabled: bool) {
+        self.erase_cursor();
+        self.cursor_enabled = enabled;
+        self.draw_cursor();
+    }
+
+    pub fn char_at(&self, col: usize, row: usize) -> Option<Cell> {
+        if row < self.text_rows && col < self.text_cols {
+            self.cells.get(row * self.text_cols + col).copied()
+        } else {
+            None
+        }
+    }
+
+    pub fn enable_read_back(&mut self) -> bool {
+        let cells = self.text_cols * self.text_rows;
+        if self.cells.len() == cells { return true; }
+        if self.cells.try_reserve_exact(cells).is_err() { return false; }
+        self.cells.resize(cells, BLANK);
+        true
+    }
+
+    pub fn enable_double_buffering(&mut self) -> bool {
+        self.surface.enable_double_buffering()
+    }
+
+    pub fn present(&mut self) {
+        self.surface.present();
+    }
+
+    pub fn end_bell(&mut self) {
+        if self.bell_pending {
+            self.surface.invert();
+            self.bell_pending = false;
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.end_bell();
+        if !core::mem::take(&mut self.written_since_tick) {
+            self.surface.present();
+        }
+    }
+
+    fn colors(&self) -> ((u8, u8, u8), (u8, u8, u8)) {
+        let fg = if self.bold { brighten(self.fg) } else { self.fg };
+        if self.reverse { (self.bg, fg) } else { (fg, self.bg) }
+    }
+
+    fn clear_cells(&mut self, row: usize, from: usize, to: usize) {
+        let (fg, bg) = self.colors();
+        for col in from..to.min(self.text_cols) {
+            self.draw(col, row, b' ', fg, bg);
+        }
+    }
+
+    fn draw(&mut self, col: usize, row: usize, ch: u8, fg: (u8, u8, u8), bg: (u8, u8, u8)) {
+        self.surface.draw_char(self.font, col, row, ch, fg, bg);
+        if let Some(cell) = self.cells.get_mut(row * self.text_cols + col) {
+            *cell = (ch, fg, bg);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.surface.clear();
+        self.cells.fill(BLANK);
+    }
+
+    fn scroll_up(&mut self) {
+        self.surface.scroll_up(self.font, self.scroll_top, self.scroll_bottom);
+        if !self.cells.is_empty() {
+            let cols = self.text_cols;
+            self.cells.copy_within((self.scroll_top + 1) * cols..(self.scroll_bottom + 1) * cols, self.scroll_top * cols);
+            self.cells[self.scroll_bottom * cols..(self.scroll_bottom + 1) * cols].fill(BLANK);
+        }
+    }
+
+    fn draw_cursor(&mut self) {
+        if !self.cursor_enabled || self.cursor_at.is_some() { return; }
+        if self.row >= self.text_rows || self.col >= self.text_cols { return; }
+        self.surface.invert_cell(self.font, self.col, self.row);
+        self.cursor_at = Some((self.row, self.col));
+    }
+
+    fn erase_cursor(&mut self) {
+        if let Some((row, col)) = self.cursor_at.take() {
+            self.surface.invert_cell(self.font, col, row);
+        }
+    }
+
+    fn write_byte(&mut self, byte: u8) {
+        if byte != 0x07 {
+            self.end_bell();
+        }
+        self.ansi_parser.handle_byte(byte);
+        while let Some(cmd) = self.ansi_parser.next_command() {
+            match cmd {
+                AnsiCommand::PrintChar(b)          => self.internal_write_byte(b),
+                AnsiCommand::SetForeground(c)      => { self.fg = c.rgb(); }
+                AnsiCommand::SetBackground(c)      => { self.bg = c.rgb(); }
+                AnsiCommand::SetForeground256(n)   => { self.fg = xterm_256_to_rgb(n); }
+                AnsiCommand::SetBackground256(n)   => { self.bg = xterm_256_to_rgb(n); }
+                AnsiCommand::SetForegroundRgb(r, g, b) => { self.fg = (r, g, b); }
+                AnsiCommand::SetBackgroundRgb(r, g, b) => { self.bg = (r, g, b); }
+                AnsiCommand::ResetAttributes       => {
+                    self.fg = self.default_fg;
+                    self.bg = self.default_bg;
+                    self.bold = false;
+                    self.reverse = false;
+                }
+                AnsiCommand::SetBold(bold)         => { self.bold = bold; }
+                AnsiCommand::SetReverse(reverse)   => { self.reverse = reverse; }
+                AnsiCommand::SetCursorPos{row, col} => {
+                    self.word_len = 0;
+                    self.row = row.min(self.text_rows.saturating_sub(1));
+                    self.col = col.min(self.text_cols.saturating_sub(1));
+                }
+                AnsiCommand::ClearScreen(ClearMode::ToEnd) => {
+                    self.clear_cells(self.row, self.col, self.text_cols);
+                    for row in self.row + 1..self.text_rows { self.clear_cells(row, 0, self.text_cols); }
+                    self.word_len = 0;
+                }
+                AnsiCommand::ClearScreen(ClearMode::ToStart) => {
+                    for row in 0..self.row { self.clear_cells(row, 0, self.text_cols); }
+                    self.clear_cells(self.row, 0, self.col + 1);
+                    self.word_len = 0;
+                }
+                AnsiCommand::ClearScreen(ClearMode::All) => {
+                    self.clear();
+                    self.word_len = 0;
+                    self.row = 0;
+                    self.col = 0;
+                }
+                AnsiCommand::ClearLine(ClearMode::ToEnd) => {
+                    self.clear_cells(self.row, self.col, self.text_cols);
+                    self.word_len = 0;
+                }
+                AnsiCommand::ClearLine(ClearMode::ToStart) => {
+                    self.clear_cells(self.row, 0, self.col + 1);
+                    self.word_len = 0;
+                }
+                AnsiCommand::ClearLine(ClearMode::All) => {
+                    self.clear_cells(self.row, 0, self.text_cols);
+                    self.word_len = 0;
+                    self.col = 0;
+                }
+                AnsiCommand::SaveCursor    => { self.saved = (self.row, self.col); }
+                AnsiCommand::RestoreCursor => {
+                    self.word_len = 0;
+                    (self.row, self.col) = self.saved;
+                }
+                AnsiCommand::MoveCursor{dx, dy} => {
+                    self.word_len = 0;
+                    self.row = self.row.saturating_add_signed(dy).min(self.text_rows.saturating_sub(1));
+                    self.col = self.col.saturating_add_signed(dx).min(self.text_cols.saturating_sub(1));
+                }
+                AnsiCommand::SetScrollRegion{top, bottom} => {
+                    let last_row = self.text_rows.saturating_sub(1);
+                    let bottom = bottom.min(last_row);
+                    (self.scroll_top, self.scroll_bottom) = if top < bottom { (top, bottom) } else { (0, last_row) };
+                    self.word_len = 0;
+                    self.row = 0;
+                    self.col = 0;
+                }
+                AnsiCommand::AlternateScreen(_) => {}
+            }
+        }
+    }
+
+    fn internal_write_byte(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.new_line(),
+            b'\r' => {
+                self.word_len = 0;
+                self.col = 0;
+            }
+            0x07 => {
+                if !self.bell_pending {
+                    self.surface.invert();
+                    self.bell_pending = true;
+                }
+            }
+            b'\t' => {
+                self.word_len = 0;
+                let next_tab = (self.col / 8 + 1) * 8;
+                self.col = next_tab.min(self.text_cols);
+            }
+            0x08 => {
+                if self.col > 0 {
+                    self.word_len = self.word_len.saturating_sub(1);
+                    self.col -= 1;
+                    let (fg, bg) = self.colors();
+                    self.draw(self.col, self.row, b' ', fg, bg);
+                }
+            }
+            byte => {
+                if self.col >= self.text_cols {
+                    match self.wrap_mode {
+                        WrapMode::CharWrap => self.new_line(),
+                        WrapMode::WordWrap => self.wrap_word(byte),
+                        WrapMode::Truncate => return,
+                    }
+                }
+                let (fg, bg) = self.colors();
+                self.draw(self.col, self.row, byte, fg, bg);
+                self.col += 1;
+                self.track_word(byte, fg, bg);
+            }
+        }
+    }
+
+    fn track_word(&mut self, byte: u8, fg: (u8, u8, u8), bg: (u8, u8, u8)) {
+        if byte == b' ' {
+            self.word_len = 0;
+        } else if self.word_len < MAX_WORD {
+            self.word[self.word_len] = (byte, fg, bg);
+            self.word_len += 1;
+        } else {
+            self.word_len = MAX_WORD + 1;
+        }
+    }
+
+    fn wrap_word(&mut self, byte: u8) {
+        let len = self.word_len;
+        if byte == b' ' || len == 0 || len >= self.text_cols || len > MAX_WORD || len > self.col {
+            self.new_line();
+            return;
+        }
+        let (_, bg) = self.colors();
+        for c in self.col - len..self.col {
+            self.draw(c, self.row, b' ', bg, bg);
+        }
+        self.new_line();
+        let word = self.word;
+        for (i, &(ch, fg, bg)) in word[..len].iter().enumerate() {
+            self.draw(i, self.row, ch, fg, bg);
+        }
+        self.col = len;
+        self.word_len = len;
+    }
+
+    fn new_line(&mut self) {
+        self.word_len = 0;
+        self.col = 0;
+        if self.text_rows == 0 { return; }
+        if self.row == self.scroll_bottom {
+            self.scroll_up();
+        } else if self.row + 1 < self.text_rows {
+            self.row += 1;
+        }
+    }
+
+    pub fn write_string(&mut self, s: &str) {
+        self.erase_cursor();
+        for byte in s.bytes() {
+            match byte {
+                0x20..=0x7E | b'\n' | b'\r' | b'\t' | 0x07 | 0x08 | 0x1B => self.write_byte(byte),
+                _ => self.write_byte(b'?'),
+            }
+        }
+        self.draw_cursor();
+        self.written_since_tick = true;
+    }
+}
+
+impl fmt::Write for FramebufferWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_string(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fonts::IBM_8X8;
+    use std::vec;
+
+    struct Frame {
+        pixels: Vec<u8>,
+        info: FrameInfo,
+    }
+
+    impl Frame {
+        fn new(width: usize, height: usize) -> Self {
+            Frame {
+                pixels: vec![0; width * height * 4],
+                info: FrameInfo { width, height, stride: width, bytes_per_pixel: 4, bgr: false },
+            }
+        }
+
+        fn writer(&mut self) -> FramebufferWriter {
+            FramebufferWriter::new(unsafe { Surface::new(self.pixels.as_mut_ptr(), self.info) })
+        }
+
+        fn pixel(&self, x: usize, y: usize) -> (u8, u8, u8) {
+            let off = (y * self.info.stride + x) * self.info.bytes_per_pixel;
+            (self.pixels[off], self.pixels[off + 1], self.pixels[off + 2])
+        }
+
+        fn cell_pixel(&self, col: usize, row: usize) -> (u8, u8, u8) {
+            self.pixel(col * TERMINUS_8X16.char_w, row * TERMINUS_8X16.char_h)
+        }
+
+        fn glyph_at(&self, col: usize, row: usize, fg: (u8, u8, u8), bg: (u8, u8, u8)) -> Vec<u8> {
+            let (w, h) = (TERMINUS_8X16.char_w, TERMINUS_8X16.char_h);
+            (0..h).map(|y| {
+                (0..w).fold(0u8, |bits, x| {
+                    let pixel = self.pixel(col * w + x, row * h + y);
+                    assert!(pixel == fg || pixel == bg, "unexpected colour {:?} at ({}, {})", pixel, x, y);
+                    bits | (((pixel == fg) as u8) << (7 - x))
+                })
+            }).collect()
+        }
+    }
+
+    const INVERTED: (u8, u8, u8) = (255, 255, 255);
+    const BLACK: (u8, u8, u8) = (0, 0, 0);
+
+    #[test]
+    fn rendered_character_matches_its_font_glyph() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.enable_cursor(false);
+
+        writer.write_string("A");
+
+        assert_eq!(frame.glyph_at(0, 0, (0, 255, 0), BLACK), TERMINUS_8X16.glyph(b'A'));
+        assert_eq!(frame.glyph_at(1, 0, (0, 255, 0), BLACK), TERMINUS_8X16.glyph(b' '));
+    }
+
+    #[test]
+    fn switching_font_recomputes_text_grid() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        assert_eq!(writer.text_size(), (8, 2));
+
+        writer.set_font(&IBM_8X8);
+        assert_eq!(writer.text_size(), (8, 4));
+
+        writer.set_font(&TERMINUS_8X16);
+        assert_eq!(writer.text_size(), (8, 2));
+    }
+
+    #[test]
+    fn switching_font_homes_the_cursor() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.write_string("abc\ndef");
+
+        writer.set_font(&IBM_8X8);
+
+        assert_eq!((writer.row, writer.col), (0, 0));
+    }
+
+    #[test]
+    fn cursor_inverts_the_cell_after_the_last_write() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+
+        writer.write_string("ab");
+
+        assert_eq!(frame.cell_pixel(2, 0), INVERTED);
+        assert_eq!(frame.cell_pixel(3, 0), BLACK);
+    }
+
+    #[test]
+    fn moving_the_cursor_restores_the_previous_cell() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.write_string("ab");
+
+        writer.write_string("\x1b[2;4H");
+
+        assert_eq!(frame.cell_pixel(2, 0), BLACK);
+        assert_eq!(frame.cell_pixel(3, 1), INVERTED);
+    }
+
+    #[test]
+    fn disabling_the_cursor_restores_the_cell() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.write_string("ab");
+
+        writer.enable_cursor(false);
+
+        assert_eq!(frame.cell_pixel(2, 0), BLACK);
+    }
+
+    fn render(text: &str, mode: WrapMode) -> Frame {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.set_wrap_mode(mode);
+        writer.write_string(text);
+        frame
+    }
+
+    #[test]
+    fn char_wrap_breaks_at_the_last_column() {
+        let wrapped = render("abcdefghij", WrapMode::CharWrap);
+        let expected = render("abcdefgh\nij", WrapMode::CharWrap);
+
+        assert!(wrapped.pixels == expected.pixels);
+    }
+
+    #[test]
+    fn word_wrap_moves_the_partial_word_to_the_next_line() {
+        let wrapped = render("hello world", WrapMode::WordWrap);
+        let expected = render("hello\nworld", WrapMode::WordWrap);
+
+        assert!(wrapped.pixels == expected.pixels);
+    }
+
+    #[test]
+    fn word_wrap_breaks_words_longer_than_a_line() {
+        let wrapped = render("abcdefghij", WrapMode::WordWrap);
+        let expected = render("abcdefgh\nij", WrapMode::CharWrap);
+
+        assert!(wrapped.pixels == expected.pixels);
+    }
+
+    #[test]
+    fn truncate_drops_characters_past_the_last_column() {
+        let truncated = render("abcdefghij", WrapMode::Truncate);
+        let expected = render("abcdefgh", WrapMode::Truncate);
+
+        assert!(truncated.pixels == expected.pixels);
+    }
+
+    #[test]
+    fn bell_keeps_frame_inverted_until_next_write() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.enable_cursor(false);
+
+        writer.write_string("\x07");
+        assert_eq!(frame.cell_pixel(4, 1), INVERTED);
+
+        writer.write_string("\r");
+        assert_eq!(frame.cell_pixel(4, 1), BLACK);
+    }
+
+    #[test]
+    fn end_bell_restores_the_frame() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.enable_cursor(false);
+        writer.write_string("\x07");
+
+        writer.end_bell();
+
+        assert_eq!(frame.cell_pixel(4, 1), BLACK);
+    }
+
+    #[test]
+    fn carriage_return_overwrites_the_current_line() {
+        let overwritten = render("abc\rX", WrapMode::CharWrap);
+        let expected = render("Xbc\r\x1b[C", WrapMode::CharWrap);
+
+        assert!(overwritten.pixels == expected.pixels);
+    }
+
+    #[test]
+    fn reset_restores_configured_default_colors() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.set_default_colors((255, 255, 255), (0, 0, 255));
+
+        writer.write_string("\x1b[31;42m\x1b[0m ");
+
+        assert_eq!((writer.fg, writer.bg), ((255, 255, 255), (0, 0, 255)));
+        assert_eq!(frame.cell_pixel(0, 0), (0, 0, 255));
+    }
+
+    #[test]
+    fn put_char_at_reads_back_without_moving_the_cursor() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        assert!(writer.enable_read_back());
+        writer.write_string("ab");
+
+        writer.put_char_at(5, 1, b'#', (255, 0, 0), (0, 0, 255));
+
+        assert_eq!(writer.char_at(5, 1), Some((b'#', (255, 0, 0), (0, 0, 255))));
+        assert_eq!(writer.char_at(1, 0), Some((b'b', (0, 255, 0), (0, 0, 0))));
+        assert_eq!((writer.row, writer.col), (0, 2));
+        assert_eq!(frame.cell_pixel(2, 0), INVERTED);
+    }
+
+    #[test]
+    fn char_at_is_none_outside_the_grid() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.enable_read_back();
+
+        assert_eq!(writer.char_at(8, 0), None);
+        assert_eq!(writer.char_at(0, 2), None);
+    }
+
+    #[test]
+    fn read_back_follows_scrolling() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.enable_read_back();
+
+        writer.write_string("top\nbottom\n");
+
+        assert_eq!(writer.char_at(0, 0), Some((b'b', (0, 255, 0), (0, 0, 0))));
+        assert_eq!(writer.char_at(0, 1), Some(BLANK));
+    }
+
+    #[test]
+    fn double_buffered_writes_leave_the_front_untouched_until_present() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        assert!(writer.enable_double_buffering());
+
+        writer.write_string("ab\n");
+        assert!(frame.pixels.iter().all(|&byte| byte == 0));
+
+        writer.present();
+        assert_eq!(frame.cell_pixel(0, 1), INVERTED);
+    }
+
+    #[test]
+    fn tick_presents_once_writing_stops() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+        writer.enable_double_buffering();
+        writer.write_string("ab");
+
+        writer.tick();
+        assert!(frame.pixels.iter().all(|&byte| byte == 0));
+
+        writer.tick();
+        assert_eq!(frame.cell_pixel(2, 0), INVERTED);
+    }
+
+    #[test]
+    fn restore_cursor_returns_to_saved_position() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+
+        writer.write_string("\x1b[2;5H\x1b[s\x1b[1;1Hxyz\x1b[u");
+
+        assert_eq!((writer.row, writer.col), (1, 4));
+    }
+
+    #[test]
+    fn text_above_scroll_region_survives_scrolling() {
+        let mut frame = Frame::new(64, 64);
+        let mut writer = frame.writer();
+        writer.enable_read_back();
+        writer.write_string("\x1b[1;1Htop\x1b[2;4r\x1b[2;1Ha\nb\nc\nd");
+
+        let first_column: Vec<u8> = (0..4).map(|row| writer.char_at(0, row).unwrap().0).collect();
+
+        assert_eq!(first_column, b"tbcd");
+    }
+
+    #[test]
+    fn relative_moves_clamp_at_screen_edges() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+
+        writer.write_string("\x1b[5A\x1b[3D");
+        assert_eq!((writer.row, writer.col), (0, 0));
+
+        writer.write_string("\x1b[9B\x1b[20C");
+        assert_eq!((writer.row, writer.col), (1, 7));
+    }
+
+    #[test]
+    fn palette_colors_set_framebuffer_colors() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+
+        writer.write_string("\x1b[38;5;231;48;5;16m");
+
+        assert_eq!((writer.fg, writer.bg), ((255, 255, 255), (0, 0, 0)));
+    }
+
+    #[test]
+    fn truecolor_stores_the_exact_foreground_tuple() {
+        let mut frame = Frame::new(64, 32);
+        let mut writer = frame.writer();
+
+        writer.write_string("\x1b[38;2;12;34;56;48;2;200;100;50mx");
+
+        assert_eq!((writer.fg, writer.bg), ((12, 34, 56), (200, 100, 50)));
+        assert_eq!(frame.cell_pixel(0, 0), (200, 100, 50));
+    }
+}
@@ -0,0 +1,7 @@
+#![cfg_attr(not(test), no_std)]
+extern crate alloc;
+
+pub mod ansi_parser;
+pub mod fonts;
+pub mod framebuffer_writer;
+pub mod text_writer;
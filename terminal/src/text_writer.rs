@@ -0,0 +1,725 @@
+use core::fmt;
+use crate::ansi_parser::{AnsiParser, AnsiCommand, AnsiColor, ClearMode, xterm_256_to_rgb};
+
+pub const BUFFER_HEIGHT: usize = 25;
+pub const BUFFER_WIDTH: usize = 80;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Color {
+    Black = 0,
+    Blue = 1,
+    Green = 2,
+    Cyan = 3,
+    Red = 4,
+    Magenta = 5,
+    Brown = 6,
+    LightGray = 7,
+    DarkGray = 8,
+    LightBlue = 9,
+    LightGreen = 10,
+    LightCyan = 11,
+    LightRed = 12,
+    Pink = 13,
+    Yellow = 14,
+    White = 15,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ColorCode(u8);
+impl ColorCode {
+    pub fn new(foreground: Color, background: Color) -> ColorCode {
+        ColorCode((background as u8) << 4 | (foreground as u8))
+    }
+
+    pub fn from_xterm(foreground: u8, background: u8) -> ColorCode {
+        let foreground = Color::from_ansi(AnsiColor::nearest(xterm_256_to_rgb(foreground)));
+        let background = Color::from_ansi(AnsiColor::nearest(xterm_256_to_rgb(background)));
+        ColorCode::new(foreground, background)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct ScreenChar {
+    ascii_character: u8,
+    color_code: ColorCode,
+}
+
+impl ScreenChar {
+    pub fn ascii_character(&self) -> u8 {
+        self.ascii_character
+    }
+
+    pub fn color_code(&self) -> ColorCode {
+        self.color_code
+    }
+}
+
+pub trait TextBuffer {
+    fn read(&self, row: usize, col: usize) -> ScreenChar;
+    fn write(&mut self, row: usize, col: usize, character: ScreenChar);
+}
+
+impl TextBuffer for [[ScreenChar; BUFFER_WIDTH]; BUFFER_HEIGHT] {
+    fn read(&self, row: usize, col: usize) -> ScreenChar {
+        self[row][col]
+    }
+
+    fn write(&mut self, row: usize, col: usize, character: ScreenChar) {
+        self[row][col] = character;
+    }
+}
+
+impl<T: TextBuffer + ?Sized> TextBuffer for &mut T {
+    fn read(&self, row: usize, col: usize) -> ScreenChar {
+        (**self).read(row, col)
+    }
+
+    fn write(&mut self, row: usize, col: usize, character: ScreenChar) {
+        (**self).write(row, col, character);
+    }
+}
+
+#[derive(Clone)]
+pub struct ScreenBuffer {
+    chars: [[ScreenChar; BUFFER_WIDTH]; BUFFER_HEIGHT],
+    cursor: (usize, usize),
+}
+
+pub struct TextWriter<B: TextBuffer> {
+    column_position: usize,
+    row_position: usize,
+    saved_position: (usize, usize),
+    scroll_top: usize,
+    scroll_bottom: usize,
+    color_code: ColorCode,
+    default_color: ColorCode,
+    bold: bool,
+    reverse: bool,
+    bell_pending: bool,
+    buffer: B,
+    primary_screen: Option<ScreenBuffer>,
+    ansi_parser: AnsiParser,
+}
+
+impl<B: TextBuffer> TextWriter<B> {
+    pub fn new(buffer: B, color_code: ColorCode) -> TextWriter<B> {
+        TextWriter {
+            column_position: 0,
+            row_position: BUFFER_HEIGHT - 1,
+            saved_position: (BUFFER_HEIGHT - 1, 0),
+            scroll_top: 0,
+            scroll_bottom: BUFFER_HEIGHT - 1,
+            color_code,
+            default_color: color_code,
+            bold: false,
+            reverse: false,
+            bell_pending: false,
+            buffer,
+            primary_screen: None,
+            ansi_parser: AnsiParser::new(),
+        }
+    }
+
+    pub fn set_default_colors(&mut self, foreground: Color, background: Color) {
+        self.default_color = ColorCode::new(foreground, background);
+        self.color_code = self.default_color;
+    }
+
+    pub fn cursor_position(&self) -> (usize, usize) {
+        (self.row_position, self.column_position)
+    }
+
+    pub fn cursor_offset(&self) -> u16 {
+        cursor_offset(self.row_position, self.column_position)
+    }
+
+    pub fn write_byte(&mut self, byte: u8) {
+        if byte != 0x07 {
+            self.end_bell();
+        }
+        self.ansi_parser.handle_byte(byte);
+        while let Some(command) = self.ansi_parser.next_command() {
+            match command {
+                AnsiCommand::PrintChar(b) => self.internal_write_byte(b),
+                AnsiCommand::SetForeground(fg) => {
+                    let bg = Color::from_u8(self.color_code.0 >> 4);
+                    self.color_code = ColorCode::new(Color::from_ansi(fg), bg);
+                }
+                AnsiCommand::SetBackground(bg) => {
+                    let fg = Color::from_u8(self.color_code.0 & 0x0F);
+                    self.color_code = ColorCode::new(fg, Color::from_ansi(bg));
+                }
+                AnsiCommand::SetForeground256(index) => {
+                    let bg = Color::from_u8(self.color_code.0 >> 4);
+                    let fg = AnsiColor::nearest(xterm_256_to_rgb(index));
+                    self.color_code = ColorCode::new(Color::from_ansi(fg), bg);
+                }
+                AnsiCommand::SetBackground256(index) => {
+                    let fg = Color::from_u8(self.color_code.0 & 0x0F);
+                    let bg = AnsiColor::nearest(xterm_256_to_rgb(index));
+                    self.color_code = ColorCode::new(fg, Color::from_ansi(bg));
+                }
+                AnsiCommand::SetForegroundRgb(r, g, b) => {
+                    let bg = Color::from_u8(self.color_code.0 >> 4);
+                    let fg = AnsiColor::nearest((r, g, b));
+                    self.color_code = ColorCode::new(Color::from_ansi(fg), bg);
+                }
+                AnsiCommand::SetBackgroundRgb(r, g, b) => {
+                    let fg = Color::from_u8(self.color_code.0 & 0x0F);
+                    let bg = AnsiColor::nearest((r, g, b));
+                    self.color_code = ColorCode::new(fg, Color::from_ansi(bg));
+                }
+                AnsiCommand::ResetAttributes => {
+                    self.color_code = self.default_color;
+                    self.bold = false;
+                    self.reverse = false;
+                }
+                AnsiCommand::SetBold(bold) => {
+                    self.bold = bold;
+                }
+                AnsiCommand::SetReverse(reverse) => {
+                    self.reverse = reverse;
+                }
+                AnsiCommand::SetCursorPos { row, col } => {
+                    self.row_position = row.min(BUFFER_HEIGHT - 1);
+                    self.column_position = col.min(BUFFER_WIDTH - 1);
+                }
+                AnsiCommand::ClearScreen(ClearMode::ToEnd) => {
+                    self.clear_cells(self.row_position, self.column_position, BUFFER_WIDTH);
+                    for row in self.row_position + 1..BUFFER_HEIGHT {
+                        self.clear_row(row);
+                    }
+                }
+                AnsiCommand::ClearScreen(ClearMode::ToStart) => {
+                    for row in 0..self.row_position {
+                        self.clear_row(row);
+                    }
+                    self.clear_cells(self.row_position, 0, self.column_position + 1);
+                }
+                AnsiCommand::ClearScreen(ClearMode::All) => {
+                    for row in 0..BUFFER_HEIGHT {
+                        self.clear_row(row);
+                    }
+                    self.row_position = 0;
+                    self.column_position = 0;
+                }
+                AnsiCommand::ClearLine(ClearMode::ToEnd) => {
+                    self.clear_cells(self.row_position, self.column_position, BUFFER_WIDTH);
+                }
+                AnsiCommand::ClearLine(ClearMode::ToStart) => {
+                    self.clear_cells(self.row_position, 0, self.column_position + 1);
+                }
+                AnsiCommand::ClearLine(ClearMode::All) => {
+                    self.clear_row(self.row_position);
+                }
+                AnsiCommand::SaveCursor => {
+                    self.saved_position = (self.row_position, self.column_position);
+                }
+                AnsiCommand::RestoreCursor => {
+                    (self.row_position, self.column_position) = self.saved_position;
+                }
+                AnsiCommand::MoveCursor { dx, dy } => {
+                    self.row_position = self.row_position.saturating_add_signed(dy).min(BUFFER_HEIGHT - 1);
+                    self.column_position = self.column_position.saturating_add_signed(dx).min(BUFFER_WIDTH - 1);
+                }
+                AnsiCommand::SetScrollRegion { top, bottom } => {
+                    let bottom = bottom.min(BUFFER_HEIGHT - 1);
+                    if top < bottom {
+                        self.scroll_top = top;
+                        self.scroll_bottom = bottom;
+                    } else {
+                        self.scroll_top = 0;
+                        self.scroll_bottom = BUFFER_HEIGHT - 1;
+                    }
+                    self.row_position = 0;
+                    self.column_position = 0;
+                }
+                AnsiCommand::AlternateScreen(true) => {
+                    if self.primary_screen.is_none() {
+                        self.primary_screen = Some(self.save_screen());
+                        for row in 0..BUFFER_HEIGHT {
+                            self.clear_row(row);
+                        }
+                        self.row_position = 0;
+                        self.column_position = 0;
+                    }
+                }
+                AnsiCommand::AlternateScreen(false) => {
+                    if let Some(screen) = self.primary_screen.take() {
+                        self.restore_screen(&screen);
+                    }
+                }
+            }
+        }
+    }
+
+    fn effective_color(&self) -> ColorCode {
+        let mut foreground = self.color_code.0 & 0x0F;
+        let mut background = self.color_code.0 >> 4;
+        if self.bold {
+            foreground |= 0x08;
+        }
+        if self.reverse {
+            (foreground, background) = (background, foreground);
+        }
+        ColorCode(background << 4 | foreground)
+    }
+
+    fn internal_write_byte(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.new_line(),
+            b'\r' => self.column_position = 0,
+            0x07 => self.visual_bell(),
+            b'\t' => {
+                let next_tab_stop = (self.column_position / 8 + 1) * 8;
+                self.column_position = next_tab_stop.min(BUFFER_WIDTH);
+            }
+            0x08 => {
+                if self.column_position > 0 {
+                    self.column_position -= 1;
+                    let row = self.row_position;
+                    let col = self.column_position;
+                    let color_code = self.effective_color();
+                    self.buffer.write(row, col, ScreenChar {
+                        ascii_character: b' ',
+                        color_code,
+                    });
+                }
+            }
+            byte => {
+                if self.column_position >= BUFFER_WIDTH {
+                    self.new_line();
+                }
+
+                let row = self.row_position;
+                let col = self.column_position;
+
+                let color_code = self.effective_color();
+                self.buffer.write(row, col, ScreenChar {
+                    ascii_character: byte,
+                    color_code,
+                });
+                self.column_position += 1;
+            }
+        }
+    }
+
+    fn new_line(&mut self) {
+        if self.row_position == self.scroll_bottom {
+            for row in self.scroll_top + 1..=self.scroll_bottom {
+                for col in 0..BUFFER_WIDTH {
+                    let character = self.buffer.read(row, col);
+                    self.buffer.write(row - 1, col, character);
+                }
+            }
+            self.clear_row(self.scroll_bottom);
+        } else if self.row_position < BUFFER_HEIGHT - 1 {
+            self.row_position += 1;
+        }
+        self.column_position = 0;
+    }
+
+    pub fn put_char_at(&mut self, col: usize, row: usize, ch: u8, color_code: ColorCode) {
+        self.end_bell();
+        if row < BUFFER_HEIGHT && col < BUFFER_WIDTH {
+            self.buffer.write(row, col, ScreenChar {
+                ascii_character: ch,
+                color_code,
+            });
+        }
+    }
+
+    pub fn char_at(&self, col: usize, row: usize) -> Option<ScreenChar> {
+        if row < BUFFER_HEIGHT && col < BUFFER_WIDTH {
+            Some(self.buffer.read(row, col))
+        } else {
+            None
+        }
+    }
+
+    pub fn save_screen(&self) -> ScreenBuffer {
+        let mut chars = [[ScreenChar { ascii_character: b' ', color_code: self.default_color }; BUFFER_WIDTH]; BUFFER_HEIGHT];
+        for (row, line) in chars.iter_mut().enumerate() {
+            for (col, character) in line.iter_mut().enumerate() {
+                *character = self.buffer.read(row, col);
+            }
+        }
+        ScreenBuffer {
+            chars,
+            cursor: (self.row_position, self.column_position),
+        }
+    }
+
+    pub fn restore_screen(&mut self, screen: &ScreenBuffer) {
+        self.end_bell();
+        for (row, line) in screen.chars.iter().enumerate() {
+            for (col, character) in line.iter().enumerate() {
+                self.buffer.write(row, col, *character);
+            }
+        }
+        (self.row_position, self.column_position) = screen.cursor;
+    }
+
+    fn visual_bell(&mut self) {
+        if !self.bell_pending {
+            self.invert_screen();
+            self.bell_pending = true;
+        }
+    }
+
+    pub fn end_bell(&mut self) {
+        if self.bell_pending {
+            self.invert_screen();
+            self.bell_pending = false;
+        }
+    }
+
+    fn invert_screen(&mut self) {
+        for row in 0..BUFFER_HEIGHT {
+            for col in 0..BUFFER_WIDTH {
+                let mut character = self.buffer.read(row, col);
+                character.color_code = ColorCode(character.color_code.0.rotate_left(4));
+                self.buffer.write(row, col, character);
+            }
+        }
+    }
+
+    fn clear_row(&mut self, row: usize) {
+        self.clear_cells(row, 0, BUFFER_WIDTH);
+    }
+
+    fn clear_cells(&mut self, row: usize, from: usize, to: usize) {
+        let blank = ScreenChar {
+            ascii_character: b' ',
+            color_code: self.effective_color(),
+        };
+        for col in from..to.min(BUFFER_WIDTH) {
+            self.buffer.write(row, col, blank);
+        }
+    }
+
+    pub fn write_string(&mut self, s: &str) {
+        for byte in s.bytes() {
+            match byte {
+                // printable ASCII byte, newline, backspace or escape
+                0x20..=0x7e | b'\n' | b'\r' | b'\t' | 0x07 | 0x08 | 0x1B => self.write_byte(byte),
+                // not part of printable ASCII range
+                _ => self.write_byte(0xfe),
+            }
+        }
+    }
+}
+
+fn cursor_offset(row: usize, col: usize) -> u16 {
+    (row.min(BUFFER_HEIGHT - 1) * BUFFER_WIDTH + col.min(BUFFER_WIDTH - 1)) as u16
+}
+
+impl Color {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Color::Black,
+            1 => Color::Blue,
+            2 => Color::Green,
+            3 => Color::Cyan,
+            4 => Color::Red,
+            5 => Color::Magenta,
+            6 => Color::Brown,
+            7 => Color::LightGray,
+            8 => Color::DarkGray,
+            9 => Color::LightBlue,
+            10 => Color::LightGreen,
+            11 => Color::LightCyan,
+            12 => Color::LightRed,
+            13 => Color::Pink,
+            14 => Color::Yellow,
+            15 => Color::White,
+            _ => Color::White,
+        }
+    }
+
+    fn from_ansi(ansi_color: AnsiColor) -> Self {
+        match ansi_color {
+            AnsiColor::Black => Color::Black,
+            AnsiColor::Red => Color::Red,
+            AnsiColor::Green => Color::Green,
+            AnsiColor::Yellow => Color::Brown,
+            AnsiColor::Blue => Color::Blue,
+            AnsiColor::Magenta => Color::Magenta,
+            AnsiColor::Cyan => Color::Cyan,
+            AnsiColor::White => Color::LightGray,
+            AnsiColor::BrightBlack => Color::DarkGray,
+            AnsiColor::BrightRed => Color::LightRed,
+            AnsiColor::BrightGreen => Color::LightGreen,
+            AnsiColor::BrightYellow => Color::Yellow,
+            AnsiColor::BrightBlue => Color::LightBlue,
+            AnsiColor::BrightMagenta => Color::Pink,
+            AnsiColor::BrightCyan => Color::LightCyan,
+            AnsiColor::BrightWhite => Color::White,
+        }
+    }
+}
+
+impl<B: TextBuffer> fmt::Write for TextWriter<B> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_string(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Screen = [[ScreenChar; BUFFER_WIDTH]; BUFFER_HEIGHT];
+
+    fn green_on_black() -> ColorCode {
+        ColorCode::new(Color::Green, Color::Black)
+    }
+
+    fn writer() -> TextWriter<Screen> {
+        let blank = ScreenChar { ascii_character: b' ', color_code: green_on_black() };
+        TextWriter::new([[blank; BUFFER_WIDTH]; BUFFER_HEIGHT], green_on_black())
+    }
+
+    #[test]
+    fn restore_cursor_returns_to_saved_position() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[5;10H\x1B[s\x1B[1;1Hxyz\x1B[u");
+
+        assert_eq!(writer.cursor_position(), (4, 9));
+    }
+
+    #[test]
+    fn text_above_scroll_region_survives_scrolling() {
+        let mut writer = writer();
+        writer.write_string("\x1B[1;1Hheader\x1B[2;25r");
+
+        writer.write_string("\x1B[25;1H");
+        for _ in 0..30 {
+            writer.write_string("line\n");
+        }
+
+        assert_eq!(writer.buffer[0][0].ascii_character, b'h');
+        assert_eq!(writer.buffer[0][5].ascii_character, b'r');
+    }
+
+    #[test]
+    fn relative_moves_clamp_at_screen_edges() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[1;1H\x1B[5A\x1B[5D");
+        assert_eq!(writer.cursor_position(), (0, 0));
+
+        writer.write_string("\x1B[100B\x1B[100C");
+        assert_eq!(writer.cursor_position(), (BUFFER_HEIGHT - 1, BUFFER_WIDTH - 1));
+    }
+
+    #[test]
+    fn palette_colors_fall_back_to_nearest_vga_color() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[38;5;196m\x1B[48;5;21mx");
+
+        assert_eq!(writer.buffer[BUFFER_HEIGHT - 1][0].color_code, ColorCode::new(Color::Red, Color::Blue));
+    }
+
+    #[test]
+    fn truecolor_falls_back_to_nearest_vga_color() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[38;2;250;250;90;48;2;0;160;170mx");
+
+        assert_eq!(writer.buffer[BUFFER_HEIGHT - 1][0].color_code, ColorCode::new(Color::Yellow, Color::Cyan));
+    }
+
+    #[test]
+    fn cursor_offset_is_row_major() {
+        assert_eq!(cursor_offset(0, 0), 0);
+        assert_eq!(cursor_offset(0, 79), 79);
+        assert_eq!(cursor_offset(1, 0), 80);
+        assert_eq!(cursor_offset(24, 79), 1999);
+    }
+
+    #[test]
+    fn cursor_offset_clamps_to_last_cell() {
+        assert_eq!(cursor_offset(30, 100), 1999);
+    }
+
+    #[test]
+    fn writer_reports_offset_of_write_position() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[3;5Hab");
+
+        assert_eq!(writer.cursor_offset(), 2 * 80 + 6);
+    }
+
+    #[test]
+    fn reverse_swaps_foreground_and_background() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[7mx\x1B[27my");
+
+        assert_eq!(writer.buffer[BUFFER_HEIGHT - 1][0].color_code, ColorCode::new(Color::Black, Color::Green));
+        assert_eq!(writer.buffer[BUFFER_HEIGHT - 1][1].color_code, green_on_black());
+    }
+
+    #[test]
+    fn bold_uses_bright_foreground() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[1mx");
+
+        assert_eq!(writer.buffer[BUFFER_HEIGHT - 1][0].color_code, ColorCode::new(Color::LightGreen, Color::Black));
+    }
+
+    fn row_text(writer: &TextWriter<Screen>, row: usize, len: usize) -> std::string::String {
+        writer.buffer[row][..len].iter().map(|c| c.ascii_character as char).collect()
+    }
+
+    #[test]
+    fn erase_to_end_of_line_keeps_text_before_cursor() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[1;1Hhello world\x1B[1;6H\x1B[K");
+
+        assert_eq!(row_text(&writer, 0, 11), "hello      ");
+        assert_eq!(writer.cursor_position(), (0, 5));
+    }
+
+    #[test]
+    fn erase_to_start_of_line_includes_cursor_cell() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[1;1Hhello world\x1B[1;6H\x1B[1K");
+
+        assert_eq!(row_text(&writer, 0, 11), "      world");
+    }
+
+    #[test]
+    fn erase_to_end_of_display_clears_following_rows() {
+        let mut writer = writer();
+
+        writer.write_string("\x1B[1;1Habc\x1B[2;1Hdef\x1B[1;2H\x1B[J");
+
+        assert_eq!(row_text(&writer, 0, 3), "a  ");
+        assert_eq!(row_text(&writer, 1, 3), "   ");
+    }
+
+    #[test]
+    fn alternate_screen_round_trip_restores_primary_screen() {
+        let mut writer = writer();
+        writer.write_string("\x1B[3;4Hshell");
+        let primary = writer.buffer;
+
+        writer.write_string("\x1B[?1049h");
+        assert_eq!(row_text(&writer, 2, 8), "        ");
+        assert_eq!(writer.cursor_position(), (0, 0));
+        writer.write_string("\x1B[10;10Happ");
+        writer.write_string("\x1B[?1049l");
+
+        assert!(writer.buffer == primary);
+        assert_eq!(writer.cursor_position(), (2, 8));
+    }
+
+    #[test]
+    fn leaving_alternate_screen_twice_is_harmless() {
+        let mut writer = writer();
+        writer.write_string("\x1B[?1049h\x1B[?1049l");
+        writer.write_string("\x1B[1;1Hkept");
+
+        writer.write_string("\x1B[?1049l");
+
+        assert_eq!(row_text(&writer, 0, 4), "kept");
+    }
+
+    #[test]
+    fn bell_keeps_screen_inverted_until_next_write() {
+        let mut writer = writer();
+        let inverted = ColorCode::new(Color::Black, Color::Green);
+
+        writer.write_string("\x07");
+        assert_eq!(writer.buffer[0][0].color_code, inverted);
+
+        writer.write_string("x");
+        assert_eq!(writer.buffer[0][0].color_code, green_on_black());
+        assert_eq!(writer.buffer[BUFFER_HEIGHT - 1][0].color_code, green_on_black());
+    }
+
+    #[test]
+    fn end_bell_restores_the_screen_once() {
+        let mut writer = writer();
+        writer.write_string("\x07\x07");
+
+        writer.end_bell();
+        writer.end_bell();
+
+        assert_eq!(writer.buffer[0][0].color_code, green_on_black());
+    }
+
+    #[test]
+    fn carriage_return_overwrites_the_current_line() {
+        let mut writer = writer();
+
+        writer.write_string("abc\rX");
+
+        assert_eq!(row_text(&writer, BUFFER_HEIGHT - 1, 3), "Xbc");
+        assert_eq!(writer.cursor_position(), (BUFFER_HEIGHT - 1, 1));
+    }
+
+    #[test]
+    fn reset_restores_configured_default_colors() {
+        let mut writer = writer();
+        writer.set_default_colors(Color::White, Color::Blue);
+
+        writer.write_string("\x1B[31;42m\x1B[0mx");
+
+        assert_eq!(writer.buffer[BUFFER_HEIGHT - 1][0].color_code, ColorCode::new(Color::White, Color::Blue));
+    }
+
+    #[test]
+    fn restoring_a_saved_screen_after_clearing_replays_the_grid() {
+        let mut writer = writer();
+        writer.write_string("\x1B[2;3H\x1B[31mboard\x1B[0m");
+        let rendered = writer.buffer;
+        let screen = writer.save_screen();
+
+        writer.write_string("\x1B[2J\x1B[1;1H");
+        assert!(writer.buffer != rendered);
+        writer.restore_screen(&screen);
+
+        assert!(writer.buffer == rendered);
+        assert_eq!(writer.cursor_position(), (1, 7));
+    }
+
+    #[test]
+    fn put_char_at_reads_back_without_moving_the_cursor() {
+        let mut writer = writer();
+        writer.write_string("ab");
+        let red_on_blue = ColorCode::new(Color::Red, Color::Blue);
+
+        writer.put_char_at(10, 3, b'#', red_on_blue);
+
+        let cell = writer.char_at(10, 3).unwrap();
+        assert_eq!((cell.ascii_character(), cell.color_code()), (b'#', red_on_blue));
+        assert_eq!(writer.cursor_position(), (BUFFER_HEIGHT - 1, 2));
+    }
+
+    #[test]
+    fn char_at_is_none_outside_the_screen() {
+        let writer = writer();
+
+        assert_eq!(writer.char_at(BUFFER_WIDTH, 0), None);
+        assert_eq!(writer.char_at(0, BUFFER_HEIGHT), None);
+    }
+
+    #[test]
+    fn xterm_colors_map_to_nearest_vga_colors() {
+        assert_eq!(ColorCode::from_xterm(9, 4), ColorCode::new(Color::LightRed, Color::Blue));
+    }
+}
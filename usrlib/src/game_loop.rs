@@ -0,0 +1,223 @@
+use crate::syscall::Syscall;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopControl {
+    Continue,
+    Pause,
+    Quit,
+}
+
+pub trait GameLoop {
+    fn update(&mut self, dt_ms: u64);
+    fn render(&self);
+    fn handle_key(&mut self, c: char) -> LoopControl;
+    fn frame_ms(&self) -> u64;
+}
+
+pub trait Clock {
+    fn now_ms(&self) -> u64;
+    fn sleep_until(&mut self, deadline_ms: u64);
+}
+
+pub trait Input {
+    fn poll(&mut self) -> Option<char>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        Syscall::uptime_ms()
+    }
+
+    fn sleep_until(&mut self, deadline_ms: u64) {
+        Syscall::sleep_until(deadline_ms);
+    }
+}
+
+pub struct Keyboard;
+
+impl Input for Keyboard {
+    fn poll(&mut self) -> Option<char> {
+        Syscall::try_read_char()
+    }
+}
+
+pub struct Driver<C: Clock, I: Input> {
+    clock: C,
+    input: I,
+    paused: bool,
+    last_frame_ms: u64,
+    next_frame_ms: u64,
+}
+
+impl<C: Clock, I: Input> Driver<C, I> {
+    pub fn new(clock: C, input: I) -> Self {
+        let last_frame_ms = clock.now_ms();
+        Driver {
+            clock,
+            input,
+            paused: false,
+            last_frame_ms,
+            next_frame_ms: last_frame_ms,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn run<G: GameLoop>(&mut self, game: &mut G) {
+        while self.tick(game) {}
+    }
+
+    pub fn tick<G: GameLoop>(&mut self, game: &mut G) -> bool {
+        game.render();
+        self.next_frame_ms += game.frame_ms();
+        self.clock.sleep_until(self.next_frame_ms);
+
+        while let Some(c) = self.input.poll() {
+            match game.handle_key(c) {
+                LoopControl::Continue => {}
+                LoopControl::Pause => self.paused = !self.paused,
+                LoopControl::Quit => return false,
+            }
+        }
+
+        let now = self.clock.now_ms();
+        if now > self.next_frame_ms + game.frame_ms() {
+            self.next_frame_ms = now;
+        }
+        if !self.paused {
+            game.update(now.saturating_sub(self.last_frame_ms));
+        }
+        self.last_frame_ms = now;
+        true
+    }
+}
+
+pub fn run<G: GameLoop>(game: &mut G) {
+    Driver::new(SystemClock, Keyboard).run(game);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+    use alloc::vec::Vec;
+
+    struct MockClock {
+        now: u64,
+        late_ms: u64,
+    }
+
+    impl Clock for MockClock {
+        fn now_ms(&self) -> u64 {
+            self.now
+        }
+
+        fn sleep_until(&mut self, deadline_ms: u64) {
+            self.now = self.now.max(deadline_ms) + self.late_ms;
+        }
+    }
+
+    struct MockInput {
+        keys: VecDeque<char>,
+    }
+
+    impl Input for MockInput {
+        fn poll(&mut self) -> Option<char> {
+            self.keys.pop_front()
+        }
+    }
+
+    struct Recorder {
+        updates: Vec<u64>,
+    }
+
+    impl GameLoop for Recorder {
+        fn update(&mut self, dt_ms: u64) {
+            self.updates.push(dt_ms);
+        }
+
+        fn render(&self) {}
+
+        fn handle_key(&mut self, c: char) -> LoopControl {
+            match c {
+                'p' => LoopControl::Pause,
+                'q' => LoopControl::Quit,
+                _ => LoopControl::Continue,
+            }
+        }
+
+        fn frame_ms(&self) -> u64 {
+            100
+        }
+    }
+
+    fn driver(keys: &str, late_ms: u64) -> Driver<MockClock, MockInput> {
+        Driver::new(
+            MockClock { now: 0, late_ms },
+            MockInput { keys: keys.chars().collect() },
+        )
+    }
+
+    #[test]
+    fn tick_updates_with_elapsed_frame_time() {
+        let mut driver = driver("", 0);
+        let mut game = Recorder { updates: Vec::new() };
+
+        assert!(driver.tick(&mut game));
+        assert!(driver.tick(&mut game));
+
+        assert_eq!(game.updates, [100, 100]);
+    }
+
+    #[test]
+    fn late_wakeups_do_not_shift_later_frames() {
+        let mut driver = driver("", 30);
+        let mut game = Recorder { updates: Vec::new() };
+
+        driver.tick(&mut game);
+        driver.tick(&mut game);
+        driver.tick(&mut game);
+
+        assert_eq!(game.updates, [130, 100, 100]);
+    }
+
+    #[test]
+    fn falling_behind_by_more_than_a_frame_resynchronises() {
+        let mut driver = driver("", 250);
+        let mut game = Recorder { updates: Vec::new() };
+
+        driver.tick(&mut game);
+        driver.tick(&mut game);
+
+        assert_eq!(game.updates, [350, 350]);
+    }
+
+    #[test]
+    fn pause_key_toggles_updates() {
+        let mut driver = driver("p", 0);
+        let mut game = Recorder { updates: Vec::new() };
+
+        driver.tick(&mut game);
+        assert!(driver.is_paused());
+        assert!(game.updates.is_empty());
+
+        driver.input.keys.push_back('p');
+        driver.tick(&mut game);
+        assert!(!driver.is_paused());
+        assert_eq!(game.updates, [100]);
+    }
+
+    #[test]
+    fn quit_key_stops_the_loop() {
+        let mut driver = driver("xq", 0);
+        let mut game = Recorder { updates: Vec::new() };
+
+        driver.run(&mut game);
+
+        assert!(game.updates.is_empty());
+    }
+}
@@ -6,3 +6,5 @@ extern crate system;
 pub mod out;
 pub mod arch;
 pub mod syscall;
+pub mod game_loop;
+pub mod random;
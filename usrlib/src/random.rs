@@ -0,0 +1,89 @@
+use crate::syscall::Syscall;
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const INCREMENT: u64 = 1442695040888963407;
+const CLOCK_SEED_SALT: u64 = 0xDEAD_BEEF_CAFE_BABE;
+
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn from_seed(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn from_clock() -> Self {
+        Rng::from_seed(CLOCK_SEED_SALT ^ Syscall::uptime_ms())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+        self.state
+    }
+
+    pub fn next_usize(&mut self, max: usize) -> usize {
+        (self.next_u64() >> 33) as usize % max
+    }
+
+    pub fn next_bool(&mut self, threshold: u64) -> bool {
+        (self.next_u64() >> 33) % 100 < threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn same_seed_produces_same_sequence() {
+        let mut a = Rng::from_seed(42);
+        let mut b = Rng::from_seed(42);
+
+        let first: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let second: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn sequence_follows_the_lcg() {
+        let mut rng = Rng::from_seed(1);
+
+        assert_eq!(rng.next_u64(), MULTIPLIER.wrapping_add(INCREMENT));
+        assert_eq!(
+            rng.next_u64(),
+            MULTIPLIER.wrapping_add(INCREMENT).wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT)
+        );
+    }
+
+    #[test]
+    fn different_seeds_diverge() {
+        let mut a = Rng::from_seed(1);
+        let mut b = Rng::from_seed(2);
+
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn next_usize_stays_within_bounds() {
+        let mut rng = Rng::from_seed(0xDEAD_BEEF_CAFE_BABE);
+
+        for max in 1..50 {
+            for _ in 0..100 {
+                assert!(rng.next_usize(max) < max);
+            }
+        }
+    }
+
+    #[test]
+    fn next_bool_respects_threshold_extremes() {
+        let mut rng = Rng::from_seed(7);
+
+        for _ in 0..100 {
+            assert!(!rng.next_bool(0));
+            assert!(rng.next_bool(100));
+        }
+    }
+}
@@ -5,6 +5,7 @@ use system::syscall_numbers::SyscallNum;
 use system::future::FutureHandle;
 use system::future::Future;
 use system::ipc::{IpcError, IpcReplyFuture, IpcServerHandle};
+use system::task::TaskInfo;
 use crate::arch;
 
 pub struct Syscall {}
@@ -15,12 +16,17 @@ impl Syscall {
         FutureHandle::unpack(raw)
     }
 
+    pub fn spawn(entry: fn(), name: &str) -> u64 {
+        arch::raw_syscall(SyscallNum::Spawn as usize, entry as usize, name.as_ptr() as usize, name.len()) as u64
+    }
+
     pub fn load(elf: &'static [u8]) -> FutureHandle {
         let elf_ptr = Box::into_raw(Box::new(elf)) as usize;
         let raw = arch::raw_syscall(SyscallNum::LoadElf as usize, elf_ptr as usize, 0, 0);
         FutureHandle::unpack(raw)
     }
 
+    /// Voluntary yield: under MLFQ the task keeps its queue level unless it has used up that level's quantum.
     pub fn task_yield() {
         arch::raw_syscall(SyscallNum::Yield as usize, 0, 0, 0);
     }
@@ -29,6 +35,44 @@ impl Syscall {
         arch::raw_syscall(SyscallNum::Sleep as usize, ms as usize, 0, 0);
     }
 
+    pub fn sleep_until(deadline_ms: u64) {
+        arch::raw_syscall(SyscallNum::SleepUntil as usize, deadline_ms as usize, 0, 0);
+    }
+
+    pub fn uptime_ms() -> u64 {
+        arch::raw_syscall(SyscallNum::Uptime as usize, 0, 0, 0) as u64
+    }
+
+    pub fn current_task_id() -> u64 {
+        arch::raw_syscall(SyscallNum::CurrentTaskId as usize, 0, 0, 0) as u64
+    }
+
+    pub fn current_task_name(buf: &mut [u8]) -> usize {
+        arch::raw_syscall(SyscallNum::CurrentTaskName as usize, buf.as_mut_ptr() as usize, buf.len(), 0)
+    }
+
+    pub fn kill(task_id: u64) -> bool {
+        arch::raw_syscall(SyscallNum::Kill as usize, task_id as usize, 0, 0) != 0
+    }
+
+    pub fn wait_for(task_id: u64) -> Option<i32> {
+        let result = arch::raw_syscall(SyscallNum::WaitForTask as usize, task_id as usize, 0, 0);
+        unsafe { *Box::from_raw(result as *mut Option<i32>) }
+    }
+
+    pub fn exit(code: i32) -> ! {
+        arch::raw_syscall(SyscallNum::Exit as usize, code as usize, 0, 0);
+        unreachable!()
+    }
+
+    pub fn list_tasks(buf: &mut [TaskInfo]) -> usize {
+        arch::raw_syscall(SyscallNum::ListTasks as usize, buf.as_mut_ptr() as usize, buf.len(), 0)
+    }
+
+    pub fn read_kernel_log(buf: &mut [u8]) -> usize {
+        arch::raw_syscall(SyscallNum::ReadKernelLog as usize, buf.as_mut_ptr() as usize, buf.len(), 0)
+    }
+
     pub fn wait_future(handle: FutureHandle) -> Box<dyn Future + Send + Sync> {
         let result = arch::raw_syscall(SyscallNum::WaitFuture as usize, handle.pack(), 0, 0);
         let r: Box<dyn Future + Send + Sync> = unsafe { *Box::from_raw(result as *mut Box<dyn Future + Send + Sync>) };
@@ -45,6 +89,10 @@ impl Syscall {
         arch::raw_syscall(SyscallNum::Print as usize, s.as_ptr() as usize, s.len(), 0);
     }
 
+    pub fn write(fd: usize, bytes: &[u8]) -> usize {
+        arch::raw_syscall(SyscallNum::Write as usize, fd, bytes.as_ptr() as usize, bytes.len())
+    }
+
     pub fn read_char() -> char {
         let c = arch::raw_syscall(SyscallNum::ReadChar as usize, 0, 0, 0);
         core::char::from_u32(c as u32).unwrap_or('\0')
@@ -59,6 +107,39 @@ impl Syscall {
         }
     }
 
+    pub fn peek_char() -> Option<char> {
+        let c = arch::raw_syscall(SyscallNum::PeekChar as usize, 0, 0, 0);
+        if c == 0 {
+            None
+        } else {
+            core::char::from_u32(c as u32)
+        }
+    }
+
+    pub fn terminal_size() -> (u16, u16) {
+        let size = arch::raw_syscall(SyscallNum::TerminalSize as usize, 0, 0, 0);
+        ((size >> 16) as u16, size as u16)
+    }
+
+    pub fn put_char_at(col: u16, row: u16, ch: u8, fg: u8, bg: u8) {
+        let position = ((row as usize) << 16) | col as usize;
+        let colors = ((bg as usize) << 8) | fg as usize;
+        arch::raw_syscall(SyscallNum::PutCharAt as usize, position, ch as usize, colors);
+    }
+
+    pub fn flush() {
+        arch::raw_syscall(SyscallNum::Flush as usize, 0, 0, 0);
+    }
+
+    pub fn read_chars(buf: &mut [char]) -> usize {
+        arch::raw_syscall(SyscallNum::ReadChars as usize, buf.as_mut_ptr() as usize, buf.len(), 0)
+    }
+
+    pub fn try_read_scancode() -> Option<u8> {
+        let scancode = arch::raw_syscall(SyscallNum::TryReadScancode as usize, 0, 0, 0);
+        u8::try_from(scancode).ok()
+    }
+
     pub fn alloc(size: usize, align: usize) -> *mut u8 {
         arch::raw_syscall(SyscallNum::Alloc as usize, size, align, 0) as *mut u8
     }
@@ -77,5 +158,9 @@ impl Syscall {
         let result = arch::raw_syscall(SyscallNum::IpcSend as usize, handle.index as usize, handle.generation as usize, value as usize);
         unsafe { *Box::from_raw(result as *mut IpcReplyFuture) }
     }
+
+    pub fn ipc_notify(handle: IpcServerHandle, value: u32) {
+        arch::raw_syscall(SyscallNum::IpcNotify as usize, handle.index as usize, handle.generation as usize, value as usize);
+    }
 }
 